@@ -0,0 +1,195 @@
+use crate::{Cli, CliArgument, CliCommand, CliOptionFlags, types::ValueHint};
+
+impl Cli {
+    // Bash can't show descriptions, the script only completes names, flags and values
+    pub(crate) fn bash_completion(&self) -> String {
+        let function = format!("_{}", self.completion_function_name());
+        let builtins = self.builtin_commands();
+        let command_names = self
+            .completion_commands()
+            .iter()
+            .map(|cmd| cmd.name.clone())
+            .chain(builtins.iter().map(|builtin| builtin.name.to_owned()))
+            .collect::<Vec<String>>();
+
+        let mut lines = vec![
+            format!("# bash completion for {}", self.executable_name),
+            String::new(),
+            format!("{}() {{", function),
+            "    local cur prev".to_owned(),
+            "    cur=\"${COMP_WORDS[COMP_CWORD]}\"".to_owned(),
+            "    prev=\"${COMP_WORDS[COMP_CWORD-1]}\"".to_owned(),
+            String::new(),
+            "    if [[ $COMP_CWORD -eq 1 ]]; then".to_owned(),
+        ];
+        if let Some(cli) = self.top_level_command() {
+            lines.push("        if [[ $cur == -* ]]; then".to_owned());
+            lines.push(format!(
+                "            COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+                bash_words(&flag_words(cli))
+            ));
+            lines.push("            return".to_owned());
+            lines.push("        fi".to_owned());
+        }
+        lines.push(format!(
+            "        COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+            bash_words(&command_names)
+        ));
+        lines.push("        return".to_owned());
+        lines.push("    fi".to_owned());
+        lines.push(String::new());
+
+        lines.push("    case \"${COMP_WORDS[1]}\" in".to_owned());
+        for cmd in self.completion_commands() {
+            lines.push(format!("        {})", bash_quote(&cmd.name)));
+            lines.extend(command_lines(cmd));
+            lines.push("            ;;".to_owned());
+        }
+        for builtin in &builtins {
+            lines.push(format!("        {})", builtin.name));
+            lines.push(format!(
+                "            COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+                bash_words(&builtin.values)
+            ));
+            lines.push("            ;;".to_owned());
+        }
+        if let Some(cli) = self.top_level_command() {
+            lines.push("        *)".to_owned());
+            lines.extend(command_lines(cli));
+            lines.push("            ;;".to_owned());
+        }
+        lines.push("    esac".to_owned());
+        lines.push("}".to_owned());
+        lines.push(String::new());
+        lines.push(format!(
+            "complete -F {} {}",
+            function,
+            bash_quote(&self.executable_name)
+        ));
+
+        lines.join("\n") + "\n"
+    }
+}
+
+// The body of a command's `case` branch: values of the option before the cursor, flags, then
+// positional values
+fn command_lines(cmd: &CliCommand) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    let value_options = cmd
+        .completion_options()
+        .into_iter()
+        .filter(|opt| !opt.args.is_empty())
+        .collect::<Vec<_>>();
+    if !value_options.is_empty() {
+        lines.push("            case \"$prev\" in".to_owned());
+        for opt in value_options {
+            let flags = match &opt.flags {
+                CliOptionFlags::Short(s) => format!("-{}", s),
+                CliOptionFlags::Long(l) => format!("--{}", l),
+                CliOptionFlags::ShortAndLong(s, l) => format!("-{}|--{}", s, l),
+            };
+            lines.push(format!("                {})", flags));
+            lines.extend(
+                reply_lines(&[&opt.args[0]])
+                    .into_iter()
+                    .map(|line| format!("                    {}", line)),
+            );
+            lines.push("                    return".to_owned());
+            lines.push("                    ;;".to_owned());
+        }
+        lines.push("            esac".to_owned());
+    }
+
+    lines.push("            if [[ $cur == -* ]]; then".to_owned());
+    lines.push(format!(
+        "                COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+        bash_words(&flag_words(cmd))
+    ));
+    lines.push("                return".to_owned());
+    lines.push("            fi".to_owned());
+    lines.extend(
+        reply_lines(&cmd.completion_arguments())
+            .into_iter()
+            .map(|line| format!("            {}", line)),
+    );
+
+    lines
+}
+
+// Fills `COMPREPLY` with the values any of the arguments accepts
+fn reply_lines(args: &[&CliArgument]) -> Vec<String> {
+    let choices = args
+        .iter()
+        .flat_map(|arg| arg.choices.iter().cloned())
+        .collect::<Vec<String>>();
+    let hints = args
+        .iter()
+        .map(|arg| arg.value_hint())
+        .collect::<Vec<ValueHint>>();
+
+    let mut sources = Vec::new();
+    if !choices.is_empty() {
+        sources.push(format!(
+            "$(compgen -W \"{}\" -- \"$cur\")",
+            bash_words(&choices)
+        ));
+    }
+    if hints.contains(&ValueHint::File) {
+        sources.push("$(compgen -f -- \"$cur\")".to_owned());
+    } else if hints.contains(&ValueHint::Dir) {
+        sources.push("$(compgen -d -- \"$cur\")".to_owned());
+    }
+
+    let mut lines = Vec::new();
+    if sources.is_empty() {
+        return lines;
+    }
+    // Adds the trailing `/` to directories and handles spaces in file names
+    if hints.iter().any(|hint| *hint != ValueHint::Text) {
+        lines.push("compopt -o filenames 2>/dev/null".to_owned());
+    }
+    lines.push(format!("COMPREPLY=({})", sources.join(" ")));
+    lines
+}
+
+fn flag_words(cmd: &CliCommand) -> Vec<String> {
+    cmd.completion_options()
+        .iter()
+        .flat_map(|opt| {
+            let [short, long] = opt.flags.values();
+            [
+                short.map(|s| format!("-{}", s)),
+                long.map(|l| format!("--{}", l)),
+            ]
+        })
+        .flatten()
+        .collect()
+}
+
+// The word list of `compgen -W "..."`, escaped for the double quotes
+fn bash_words(words: &[String]) -> String {
+    words
+        .iter()
+        .map(|word| {
+            word.chars()
+                .flat_map(|c| match c {
+                    '"' | '\\' | '$' | '`' => vec!['\\', c],
+                    _ => vec![c],
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+// Single quotes, unless the word is safe as it is
+fn bash_quote(word: &str) -> String {
+    if word
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-_./".contains(c))
+    {
+        return word.to_owned();
+    }
+    format!("'{}'", word.replace('\'', "'\\''"))
+}
@@ -0,0 +1,141 @@
+use crate::{
+    Cli, CliArgument, CliCommand, CliOption,
+    completion::{BuiltinCommand, first_line},
+    types::ValueHint,
+};
+
+impl Cli {
+    pub(crate) fn fish_completion(&self) -> String {
+        let exe = fish_quote(&self.executable_name);
+        let mut lines = vec![
+            format!("# fish completion for {}", self.executable_name),
+            String::new(),
+            // Files are only offered where a value hint asks for them
+            format!("complete -c {} -f", exe),
+        ];
+
+        // Before a command is typed: the commands, and the options and arguments of `cli`
+        let top_level = "__fish_use_subcommand".to_owned();
+        lines.push(String::new());
+        for cmd in self.completion_commands() {
+            lines.push(format!(
+                "complete -c {} -n {} -a {}{}",
+                exe,
+                top_level,
+                fish_quote(&cmd.name),
+                description_flag(cmd.description.as_deref())
+            ));
+        }
+        let builtins = self.builtin_commands();
+        for builtin in &builtins {
+            lines.push(format!(
+                "complete -c {} -n {} -a {}{}",
+                exe,
+                top_level,
+                fish_quote(builtin.name),
+                description_flag(Some(&builtin.description))
+            ));
+        }
+        if let Some(cli) = self.top_level_command() {
+            lines.extend(command_lines(&exe, &top_level, cli));
+        }
+
+        for cmd in self.completion_commands() {
+            let condition = fish_quote(&format!("__fish_seen_subcommand_from {}", cmd.name));
+            let cmd_lines = command_lines(&exe, &condition, cmd);
+            if !cmd_lines.is_empty() {
+                lines.push(String::new());
+                lines.extend(cmd_lines);
+            }
+        }
+        lines.push(String::new());
+        for builtin in &builtins {
+            lines.push(builtin_line(&exe, builtin));
+        }
+
+        lines.join("\n") + "\n"
+    }
+}
+
+// One `complete` line per option, then one for each argument that has something to offer
+fn command_lines(exe: &str, condition: &str, cmd: &CliCommand) -> Vec<String> {
+    let mut lines = cmd
+        .completion_options()
+        .into_iter()
+        .map(|opt| format!("complete -c {} -n {}{}", exe, condition, option_flags(opt)))
+        .collect::<Vec<String>>();
+    for arg in cmd.completion_arguments() {
+        let values = value_flags(arg, false);
+        if !values.is_empty() {
+            lines.push(format!(
+                "complete -c {} -n {}{}{}",
+                exe,
+                condition,
+                values,
+                description_flag(arg.description.as_deref())
+            ));
+        }
+    }
+    lines
+}
+
+// e.g. ` -s o -l output -r -F -d 'Where to write'`
+fn option_flags(opt: &CliOption) -> String {
+    let [short, long] = opt.flags.values();
+    let mut flags = String::new();
+    if let Some(short) = short {
+        flags.push_str(&format!(" -s {}", fish_quote(&short)));
+    }
+    if let Some(long) = long {
+        flags.push_str(&format!(" -l {}", fish_quote(&long)));
+    }
+    // Only the first value of an option is completed, fish has no notion of the ones after it
+    if let Some(arg) = opt.args.first() {
+        flags.push_str(&value_flags(arg, true));
+    }
+    flags.push_str(&description_flag(opt.description.as_deref()));
+    flags
+}
+
+// What a value completes to, `requires_value` marks it as the parameter of an option
+fn value_flags(arg: &CliArgument, requires_value: bool) -> String {
+    if !arg.choices.is_empty() {
+        let choices = arg
+            .choices
+            .iter()
+            .map(|choice| choice.replace(' ', "\\ "))
+            .collect::<Vec<String>>();
+        let exclusive = if requires_value { " -x" } else { "" };
+        return format!("{} -a {}", exclusive, fish_quote(&choices.join(" ")));
+    }
+    match (arg.value_hint(), requires_value) {
+        (ValueHint::Text, true) => " -x".to_owned(),
+        (ValueHint::Text, false) => String::new(),
+        (ValueHint::File, true) => " -r -F".to_owned(),
+        (ValueHint::File, false) => " -F".to_owned(),
+        (ValueHint::Dir, true) => " -x -a '(__fish_complete_directories)'".to_owned(),
+        (ValueHint::Dir, false) => " -a '(__fish_complete_directories)'".to_owned(),
+    }
+}
+
+fn builtin_line(exe: &str, builtin: &BuiltinCommand) -> String {
+    format!(
+        "complete -c {} -n {} -a {}",
+        exe,
+        fish_quote(&format!("__fish_seen_subcommand_from {}", builtin.name)),
+        fish_quote(&builtin.values.join(" "))
+    )
+}
+
+fn description_flag(description: Option<&str>) -> String {
+    let description = first_line(description);
+    if description.is_empty() {
+        return String::new();
+    }
+    format!(" -d {}", fish_quote(&description))
+}
+
+// Inside single quotes fish only treats `\` and `'` specially
+fn fish_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
@@ -0,0 +1,129 @@
+use std::io::{self, Write};
+
+use crate::{Cli, CliArgument, CliCommand, CliOption, locale::Message};
+
+mod bash;
+mod fish;
+mod zsh;
+
+// The shells `Cli::generate_completion` can write a script for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    pub const ALL: [Shell; 3] = [Shell::Bash, Shell::Zsh, Shell::Fish];
+
+    // The value taken by the built-in `completions <shell>` command
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+        }
+    }
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim().to_lowercase();
+        Self::ALL.into_iter().find(|shell| shell.name() == name)
+    }
+}
+
+// A command the scripts complete without it being defined, e.g. `help <command>`
+pub(crate) struct BuiltinCommand {
+    pub(crate) name: &'static str,
+    pub(crate) description: String,
+    // Its single argument, and what it completes to
+    pub(crate) value_name: &'static str,
+    pub(crate) values: Vec<String>,
+}
+
+impl Cli {
+    // Writes a static completion script for the shell, built from the commands, arguments and
+    // options. Hidden items are left out, like in the help.
+    pub fn generate_completion(&self, shell: Shell, out: &mut impl Write) -> io::Result<()> {
+        let script = match shell {
+            Shell::Bash => self.bash_completion(),
+            Shell::Zsh => self.zsh_completion(),
+            Shell::Fish => self.fish_completion(),
+        };
+        out.write_all(script.as_bytes())
+    }
+
+    // Every command that can be typed as the first token, except `cli`
+    pub(crate) fn completion_commands(&self) -> Vec<&CliCommand> {
+        self.commands
+            .iter()
+            .filter(|cmd| cmd.name != "cli" && !cmd.is_hidden())
+            .collect()
+    }
+    // The options and arguments completed before any command is typed
+    pub(crate) fn top_level_command(&self) -> Option<&CliCommand> {
+        self.commands.iter().find(|cmd| cmd.name == "cli")
+    }
+    pub(crate) fn builtin_commands(&self) -> Vec<BuiltinCommand> {
+        let mut builtins = vec![BuiltinCommand {
+            name: "help",
+            description: self.message(Message::HelpCommandDescription),
+            value_name: "command",
+            values: self
+                .completion_commands()
+                .iter()
+                .map(|cmd| cmd.name.clone())
+                .collect(),
+        }];
+        if self.has_completions_command() {
+            builtins.push(BuiltinCommand {
+                name: "completions",
+                description: self.message(Message::CompletionsCommandDescription),
+                value_name: "shell",
+                values: Shell::ALL
+                    .iter()
+                    .map(|shell| shell.name().to_owned())
+                    .collect(),
+            });
+        }
+        builtins
+    }
+    // Whether `completions <shell>` is built in, a command of the same name takes priority
+    pub(crate) fn has_completions_command(&self) -> bool {
+        self.completions_command && !self.commands.iter().any(|cmd| cmd.name == "completions")
+    }
+    // Shell functions are named after the executable, e.g. `my_app` for `my-app`
+    pub(crate) fn completion_function_name(&self) -> String {
+        self.executable_name
+            .chars()
+            .map(|c| match c.is_ascii_alphanumeric() {
+                true => c,
+                false => '_',
+            })
+            .collect()
+    }
+}
+
+impl CliCommand {
+    pub(crate) fn completion_options(&self) -> Vec<&CliOption> {
+        self.options.iter().filter(|opt| !opt.is_hidden()).collect()
+    }
+    pub(crate) fn completion_arguments(&self) -> Vec<&CliArgument> {
+        self.arguments.iter().filter(|arg| !arg.hidden).collect()
+    }
+}
+
+impl CliOption {
+    // Map options and delimited lists collect their values over repeated occurrences
+    pub(crate) fn is_repeatable(&self) -> bool {
+        self.args.len() == 1 && (self.args[0].map || self.args[0].delimiter.is_some())
+    }
+}
+
+// Completion menus show a single line per entry
+pub(crate) fn first_line(description: Option<&str>) -> String {
+    description
+        .and_then(|d| d.trim().lines().next())
+        .unwrap_or_default()
+        .trim()
+        .to_owned()
+}
@@ -0,0 +1,251 @@
+use crate::{
+    Cli, CliArgument, CliCommand, CliOption, CliOptionFlags,
+    completion::{BuiltinCommand, first_line},
+    types::ValueHint,
+};
+
+impl Cli {
+    pub(crate) fn zsh_completion(&self) -> String {
+        let function = format!("_{}", self.completion_function_name());
+        let builtins = self.builtin_commands();
+
+        let mut lines = vec![
+            format!("#compdef {}", self.executable_name),
+            String::new(),
+            format!("{}() {{", function),
+            "    local context state state_descr line".to_owned(),
+            "    typeset -A opt_args".to_owned(),
+            String::new(),
+        ];
+
+        // Top-level options, then the command, then whatever follows it
+        let mut specs = self
+            .top_level_command()
+            .map(|cli| cli.completion_options())
+            .unwrap_or_default()
+            .into_iter()
+            .map(option_spec)
+            .collect::<Vec<String>>();
+        specs.push("'1: :->command'".to_owned());
+        specs.push("'*:: :->args'".to_owned());
+        lines.extend(arguments_call("_arguments -C", &specs, 4));
+        lines.push(String::new());
+
+        lines.push("    case $state in".to_owned());
+        lines.push("        command)".to_owned());
+        lines.push("            local -a commands".to_owned());
+        lines.push("            commands=(".to_owned());
+        for cmd in self.completion_commands() {
+            lines.push(format!(
+                "                {}",
+                zsh_quote(&format!(
+                    "{}:{}",
+                    escape_colons(&cmd.name),
+                    first_line(cmd.description.as_deref())
+                ))
+            ));
+        }
+        for builtin in &builtins {
+            lines.push(format!(
+                "                {}",
+                zsh_quote(&format!("{}:{}", builtin.name, builtin.description))
+            ));
+        }
+        lines.push("            )".to_owned());
+        lines.push("            _describe -t commands command commands".to_owned());
+        lines.push("            ;;".to_owned());
+
+        lines.push("        args)".to_owned());
+        lines.push("            case $words[1] in".to_owned());
+        for cmd in self.completion_commands() {
+            lines.push(format!("                {})", zsh_quote(&cmd.name)));
+            lines.extend(arguments_call("_arguments", &command_specs(cmd), 20));
+            lines.push("                    ;;".to_owned());
+        }
+        for builtin in &builtins {
+            lines.push(format!("                {})", builtin.name));
+            lines.extend(arguments_call("_arguments", &[builtin_spec(builtin)], 20));
+            lines.push("                    ;;".to_owned());
+        }
+        if let Some(cli) = self.top_level_command() {
+            let specs = positional_specs(cli);
+            if !specs.is_empty() {
+                lines.push("                *)".to_owned());
+                lines.extend(arguments_call("_arguments", &specs, 20));
+                lines.push("                    ;;".to_owned());
+            }
+        }
+        lines.push("            esac".to_owned());
+        lines.push("            ;;".to_owned());
+        lines.push("    esac".to_owned());
+        lines.push("}".to_owned());
+        lines.push(String::new());
+
+        // Works both from a file in `$fpath` and when sourced
+        lines.push("if [[ $zsh_eval_context[-1] == loadautofunc ]]; then".to_owned());
+        lines.push(format!("    {} \"$@\"", function));
+        lines.push("else".to_owned());
+        lines.push(format!(
+            "    compdef {} {}",
+            function,
+            zsh_quote(&self.executable_name)
+        ));
+        lines.push("fi".to_owned());
+
+        lines.join("\n") + "\n"
+    }
+}
+
+// `_arguments` with one spec per line
+fn arguments_call(call: &str, specs: &[String], indent: usize) -> Vec<String> {
+    let padding = " ".repeat(indent);
+    if specs.is_empty() {
+        return vec![format!("{}{}", padding, call)];
+    }
+
+    let mut lines = vec![format!("{}{} \\", padding, call)];
+    for (idx, spec) in specs.iter().enumerate() {
+        let continuation = if idx + 1 < specs.len() { " \\" } else { "" };
+        lines.push(format!("{}    {}{}", padding, spec, continuation));
+    }
+    lines
+}
+
+fn command_specs(cmd: &CliCommand) -> Vec<String> {
+    cmd.completion_options()
+        .into_iter()
+        .map(option_spec)
+        .chain(positional_specs(cmd))
+        .collect()
+}
+
+fn positional_specs(cmd: &CliCommand) -> Vec<String> {
+    let mut specs = Vec::new();
+    let mut position = 1;
+    for (idx, arg) in cmd.arguments.iter().enumerate() {
+        if arg.variadic && arg.delimiter.is_none() {
+            specs.push(rest_spec(&cmd.arguments[idx..]));
+            break;
+        }
+        // Tuple and array arguments take one position per value
+        for value in 0..arg.arity.unwrap_or(1) {
+            // Hidden arguments keep their position, they are just not described
+            let spec = match arg.hidden {
+                true => format!("{}: :", position),
+                false => format!("{}{}", position, value_spec(arg, value)),
+            };
+            specs.push(zsh_quote(&spec));
+            position += 1;
+        }
+    }
+    specs
+}
+
+// `*:files:_files`. Positions after a variadic argument can't be told apart from it, so they
+// share its spec, e.g. `*:src or dst:_files` for `<src...> <dst>`
+fn rest_spec(args: &[CliArgument]) -> String {
+    let visible = args
+        .iter()
+        .filter(|arg| !arg.hidden)
+        .collect::<Vec<&CliArgument>>();
+    let names = visible
+        .iter()
+        .map(|arg| escape_colons(&arg.placeholder(0)))
+        .collect::<Vec<String>>();
+    let actions = visible
+        .iter()
+        .map(|arg| value_action(arg))
+        .collect::<Vec<String>>();
+    let action = match actions.iter().all(|action| *action == actions[0]) {
+        true => actions.first().cloned().unwrap_or_default(),
+        false => String::new(),
+    };
+    let message = match names.is_empty() {
+        true => " ".to_owned(),
+        false => names.join(" or "),
+    };
+    zsh_quote(&format!("*:{}:{}", message, action))
+}
+
+// e.g. `'(-o --output)'{-o,--output}'[Where to write]:path:_files'`
+fn option_spec(opt: &CliOption) -> String {
+    let description = escape_brackets(&first_line(opt.description.as_deref()));
+    let values = opt
+        .args
+        .iter()
+        .flat_map(|arg| (0..arg.arity.unwrap_or(1)).map(|position| value_spec(arg, position)))
+        .collect::<String>();
+    let tail = match description.is_empty() {
+        true => values,
+        false => format!("[{}]{}", description, values),
+    };
+    let repeat = if opt.is_repeatable() { "*" } else { "" };
+
+    match &opt.flags {
+        CliOptionFlags::Short(s) => zsh_quote(&format!("{}-{}{}", repeat, s, tail)),
+        CliOptionFlags::Long(l) => zsh_quote(&format!("{}--{}{}", repeat, l, tail)),
+        CliOptionFlags::ShortAndLong(s, l) => {
+            let exclusion = match opt.is_repeatable() {
+                true => "'*'".to_owned(),
+                false => format!("'(-{} --{})'", s, l),
+            };
+            format!("{}{{-{},--{}}}{}", exclusion, s, l, zsh_quote(&tail))
+        }
+    }
+}
+
+// `:message:action`, with an extra `:` in front when the value can be left out. Tuple and array
+// values get one spec each, named after their position
+fn value_spec(arg: &CliArgument, position: usize) -> String {
+    let optional = if arg.optional { ":" } else { "" };
+    format!(
+        ":{}{}:{}",
+        optional,
+        escape_colons(&arg.placeholder(position)),
+        value_action(arg)
+    )
+}
+
+// `(fast slow)` for choices, `_files` for paths, nothing for text
+fn value_action(arg: &CliArgument) -> String {
+    if !arg.choices.is_empty() {
+        let choices = arg
+            .choices
+            .iter()
+            .map(|choice| escape_colons(choice).replace(' ', "\\ "))
+            .collect::<Vec<String>>();
+        return format!("({})", choices.join(" "));
+    }
+    match arg.value_hint() {
+        ValueHint::Text => String::new(),
+        ValueHint::File => "_files".to_owned(),
+        ValueHint::Dir => "_files -/".to_owned(),
+    }
+}
+
+fn builtin_spec(builtin: &BuiltinCommand) -> String {
+    zsh_quote(&format!(
+        "1:{}:({})",
+        builtin.value_name,
+        builtin
+            .values
+            .iter()
+            .map(|value| escape_colons(value))
+            .collect::<Vec<String>>()
+            .join(" ")
+    ))
+}
+
+fn zsh_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+fn escape_colons(text: &str) -> String {
+    text.replace('\\', "\\\\").replace(':', "\\:")
+}
+
+fn escape_brackets(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+}
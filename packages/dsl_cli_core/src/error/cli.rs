@@ -1,181 +1,352 @@
+use std::fmt::{self, Write};
+
 use crate::{
     Cli,
-    error::{ParseError, suggest_similar::suggest_similar},
-    types::CliOptionFlags,
+    completion::Shell,
+    error::{ErrorFormat, ParseError, ParseErrorKind},
+    help::json::HELP_FORMATS,
+    json::Json,
+    locale::{HelpTopic, Message},
+    style::Stream,
+    types::{CliOption, CliOptionFlags},
 };
 
 impl Cli {
+    // Writes the error to stderr, as text or JSON depending on the error format
     pub fn handle_parse_error(&self, e: ParseError) {
-        match e {
-            ParseError::InvalidCommand(command) => {
-                eprintln!("error: Invalid command: {}\n", command);
-
-                println!(
-                    "tip: Available commands: {}",
-                    self.commands
-                        .iter()
-                        .map(|cmd| cmd.name.clone())
-                        .collect::<Vec<String>>()
-                        .join(", ")
-                );
-                println!(
-                    "( For more help on commands run: {} help )",
-                    self.executable_name
-                );
-            }
-            ParseError::TooManyArguments(args) => {
-                eprintln!(
-                    "error: Arguments: {:?} exceeded the maximum number of arguments\n",
-                    args
-                );
+        let text = match self.error_format() {
+            ErrorFormat::Json => self.error_report(&e).to_json() + "\n",
+            ErrorFormat::Text => {
+                let mut text = String::new();
+                self.render_parse_error(e, &mut text).unwrap();
+                text
+            }
+        };
+        self.write_to(Stream::Stderr, &text);
+    }
+    // Writes `warning: <message>` to stderr, a `{"warning": ...}` line with the JSON error format
+    pub(crate) fn warn(&self, message: &str) {
+        let text = match self.error_format() {
+            ErrorFormat::Json => Json::object([("warning", Json::string(message))]).to_string(),
+            ErrorFormat::Text => {
+                let label = self.message(Message::WarningLabel);
+                let theme = self.theme_for(Stream::Stderr);
+                format!("{} {}", theme.warning.paint(&label), message)
+            }
+        };
+        self.write_to(Stream::Stderr, &(text + "\n"));
+    }
+    fn render_parse_error(&self, e: ParseError, out: &mut String) -> fmt::Result {
+        let theme = self.theme_for(Stream::Stderr);
+        let error = theme.error.paint(&self.message(Message::ErrorLabel));
+        let tip = theme.tip.paint(&self.message(Message::TipLabel));
 
-                let used_command = self.used_command.as_ref().unwrap();
-                let command_def = self
-                    .commands
-                    .iter()
-                    .find(|cmd| &cmd.name == used_command)
-                    .unwrap();
+        writeln!(out, "{} {}", error, self.error_message(&e))?;
+        for line in self.error_snippet(e.token_index()) {
+            writeln!(out, "{}", line)?;
+        }
+        writeln!(out)?;
 
-                println!(
-                    "tip: Arguments for '{}' command are: {}",
-                    used_command,
-                    command_def
-                        .arguments
-                        .iter()
-                        .map(|arg| arg.reconstruct_name())
-                        .collect::<Vec<String>>()
-                        .join(", ")
-                );
-                println!(
-                    "( For more help on arguments run: {} help {} )",
-                    self.executable_name, used_command
-                );
-            }
-            ParseError::MissingRequiredArguments(args) => {
-                eprintln!("error: Missing required arguments: {:?}\n", args.join(" "));
+        let suggestion = self.did_you_mean(&self.typo_suggestions(&e));
+        let help_command = match self.used_command.as_deref() {
+            Some(used_command) if e.kind() != ParseErrorKind::InvalidCommand => {
+                format!("{} help {}", self.executable_name, used_command)
+            }
+            _ => format!("{} help", self.executable_name),
+        };
+        let more_help = |topic| self.message(Message::MoreHelp(topic, &help_command));
 
+        match e {
+            ParseError::InvalidCommand(..) => {
+                if suggestion.is_empty() {
+                    let commands = self.command_names().join(", ");
+                    writeln!(
+                        out,
+                        "{} {}",
+                        tip,
+                        self.message(Message::AvailableCommands(&commands))
+                    )?;
+                } else {
+                    writeln!(out, "{} {}", tip, suggestion)?;
+                }
+                writeln!(out, "{}", more_help(HelpTopic::Commands))?;
+            }
+            // Only `completions <shell>` is a built-in command with a required argument
+            ParseError::MissingRequiredArguments(..) if self.builtin_usage.is_some() => {
+                let shells = Shell::ALL.map(|shell| shell.name()).join(", ");
+                writeln!(
+                    out,
+                    "{} {}",
+                    tip,
+                    self.message(Message::PossibleValues(&shells))
+                )?;
+            }
+            ParseError::TooManyArguments(..) | ParseError::MissingRequiredArguments(..) => {
                 let used_command = self.used_command.as_ref().unwrap();
                 let command_def = self
                     .commands
                     .iter()
                     .find(|cmd| &cmd.name == used_command)
                     .unwrap();
-
-                println!(
-                    "tip: Arguments for '{}' command are: {}",
-                    used_command,
-                    command_def
-                        .arguments
-                        .iter()
-                        .map(|arg| arg.reconstruct_name())
-                        .collect::<Vec<String>>()
-                        .join(", ")
-                );
-                println!(
-                    "( For more help on required arguments run: {} help {} )",
-                    self.executable_name, used_command
-                );
-            }
-            ParseError::MissingRequiredOptions(opts) => {
-                eprintln!("error: Missing required options: {:?}\n", opts.join(", "));
-
-                let used_command = self.used_command.as_ref().unwrap();
-                let command_def = self
-                    .commands
+                let args = command_def
+                    .arguments
                     .iter()
-                    .find(|cmd| &cmd.name == used_command)
-                    .unwrap();
-
-                println!(
-                    "tip: Options for '{}' command are: {}",
-                    used_command,
-                    command_def
-                        .options
-                        .iter()
-                        .map(|opt| format!("({})", opt.flags.to_string()))
-                        .collect::<Vec<String>>()
-                        .join(", ")
-                );
-                println!(
-                    "( For more help on options run: {} help {} )",
-                    self.executable_name, used_command
-                );
-            }
-            ParseError::MissingRequiredArgumentsForOption(idx, args) => {
-                eprintln!(
-                    "error: Missing required arguments for option: {:?}\n",
-                    args.join(" ")
-                );
+                    .filter(|arg| !arg.hidden)
+                    .map(|arg| arg.reconstruct_name())
+                    .collect::<Vec<String>>()
+                    .join(", ");
 
+                writeln!(
+                    out,
+                    "{} {}",
+                    tip,
+                    self.message(Message::CommandArguments(used_command, &args))
+                )?;
+                let topic = match e {
+                    ParseError::TooManyArguments(..) => HelpTopic::Arguments,
+                    _ => HelpTopic::RequiredArguments,
+                };
+                writeln!(out, "{}", more_help(topic))?;
+            }
+            ParseError::MissingRequiredOptions(..) => {
                 let used_command = self.used_command.as_ref().unwrap();
                 let command_def = self
                     .commands
                     .iter()
                     .find(|cmd| &cmd.name == used_command)
                     .unwrap();
+                let opts = command_def
+                    .options
+                    .iter()
+                    .filter(|opt| !opt.is_hidden())
+                    .map(|opt| format!("({})", opt.flags))
+                    .collect::<Vec<String>>()
+                    .join(", ");
 
-                let opt_def = &command_def.options[idx];
-
-                println!(
-                    "tip: Option '{}' is defined as: {} {}",
-                    used_command,
-                    opt_def.flags.to_string(),
-                    opt_def
-                        .args
-                        .iter()
-                        .map(|arg| arg.reconstruct_name())
-                        .collect::<Vec<String>>()
-                        .join(" ")
-                );
-                println!(
-                    "( For more help on option arguments run: {} help {} )",
-                    self.executable_name, used_command
-                );
-            }
-            ParseError::InvalidOptionFlag(flag) => {
-                eprintln!("error: Invalid option flag: {:?}\n", flag);
+                writeln!(
+                    out,
+                    "{} {}",
+                    tip,
+                    self.message(Message::CommandOptions(used_command, &opts))
+                )?;
+                writeln!(out, "{}", more_help(HelpTopic::Options))?;
+            }
+            ParseError::TooManyArgumentsForOption(_, idx, _)
+            | ParseError::MissingRequiredArgumentsForOption(_, idx, _)
+            | ParseError::InvalidMapEntry(_, idx, _)
+            | ParseError::InvalidOptionValue(_, idx, _) => {
+                let opt_def = self.option_def(idx);
+                let definition = std::iter::once(opt_def.flags.to_string())
+                    .chain(opt_def.args.iter().map(|arg| arg.reconstruct_name()))
+                    .collect::<Vec<String>>()
+                    .join(" ");
 
+                writeln!(
+                    out,
+                    "{} {}",
+                    tip,
+                    self.message(Message::OptionDefinedAs(&definition))
+                )?;
+                writeln!(out, "{}", more_help(HelpTopic::OptionArguments))?;
+            }
+            ParseError::TooFewValues(..) | ParseError::TooManyValues(..) => {
+                writeln!(out, "{}", more_help(HelpTopic::Arguments))?;
+            }
+            ParseError::InvalidChoice(_, _, _, choices) => {
+                if !suggestion.is_empty() {
+                    writeln!(out, "{} {}", tip, suggestion)?;
+                }
+                writeln!(
+                    out,
+                    "{} {}",
+                    tip,
+                    self.message(Message::PossibleValues(&choices.join(", ")))
+                )?;
+                // The help doesn't document the values of built-in commands
+                if self.builtin_usage.is_none() {
+                    writeln!(out, "{}", more_help(HelpTopic::Arguments))?;
+                }
+            }
+            ParseError::InvalidHelpFormat(..) => {
+                if !suggestion.is_empty() {
+                    writeln!(out, "{} {}", tip, suggestion)?;
+                }
+                let formats = HELP_FORMATS.join(", ");
+                writeln!(
+                    out,
+                    "{} {}",
+                    tip,
+                    self.message(Message::PossibleValues(&formats))
+                )?;
+            }
+            ParseError::DuplicateMapKey(..) => {
+                writeln!(out, "{} {}", tip, self.message(Message::UniqueMapKeys))?;
+                writeln!(out, "{}", more_help(HelpTopic::OptionArguments))?;
+            }
+            ParseError::InvalidOptionFlag(_, flag) => {
                 let used_command = self.used_command.as_ref().unwrap();
-                let command_def = self
+                let (short_flags, long_flags) = self.used_command_flags();
+
+                // The flag may belong to another command, e.g. `pull --force`
+                let owners = self
                     .commands
                     .iter()
-                    .find(|cmd| &cmd.name == used_command)
-                    .unwrap();
+                    .filter(|cmd| !cmd.is_hidden())
+                    .filter(|cmd| {
+                        cmd.options
+                            .iter()
+                            .any(|opt| opt.flags == flag && !opt.is_hidden())
+                    })
+                    .map(|cmd| format!("`{}`", cmd.name))
+                    .collect::<Vec<String>>();
 
-                let mut long_flags = Vec::new();
-                let mut short_flags = Vec::new();
-
-                for opt in command_def.options.iter() {
-                    match &opt.flags {
-                        CliOptionFlags::Short(s) => {
-                            short_flags.push(format!("-{}", s));
-                        }
-                        CliOptionFlags::Long(l) => {
-                            long_flags.push(format!("--{}", l));
-                        }
-                        CliOptionFlags::ShortAndLong(s, l) => {
-                            short_flags.push(format!("-{}", s));
-                            long_flags.push(format!("--{}", l));
-                        }
-                    }
+                // No list of flags when the command has none of that kind
+                let hint = if !owners.is_empty() {
+                    Some(self.message(Message::OptionOfOtherCommand(
+                        &flag,
+                        &owners.join(", "),
+                        used_command,
+                    )))
+                } else if !suggestion.is_empty() {
+                    Some(suggestion)
+                } else if flag.starts_with("--") {
+                    (!long_flags.is_empty()).then(|| {
+                        self.message(Message::AvailableFlags(
+                            used_command,
+                            &long_flags.join(", "),
+                        ))
+                    })
+                } else {
+                    (!short_flags.is_empty()).then(|| {
+                        self.message(Message::AvailableShortFlags(
+                            used_command,
+                            &short_flags.join(", "),
+                        ))
+                    })
+                };
+                if let Some(hint) = hint {
+                    writeln!(out, "{} {}", tip, hint)?;
                 }
+                writeln!(out, "{}", more_help(HelpTopic::Options))?;
+            }
+        }
 
-                if flag.starts_with("--") {
-                    println!("tip: {}", suggest_similar(flag, long_flags));
-                } else {
-                    println!(
-                        "tip: Available short flags for `{}` are: {}",
-                        used_command,
-                        short_flags.join(", ")
-                    );
+        // A mistyped command falls back to `cli`, which then fails on the token
+        if self.used_command.as_deref() == Some("cli")
+            && let Some(token) = &self.unmatched_command
+        {
+            let suggestion = self.did_you_mean(&self.command_suggestions());
+            if !suggestion.is_empty() {
+                writeln!(
+                    out,
+                    "{} {}",
+                    tip,
+                    self.message(Message::NotACommand(token, &suggestion))
+                )?;
+            }
+        }
+        writeln!(out)?;
+
+        Ok(())
+    }
+
+    // The one-line description of the error, printed after `error:`
+    pub(crate) fn error_message(&self, e: &ParseError) -> String {
+        let flags = |idx: &usize| self.option_def(*idx).flags.to_string();
+
+        match e {
+            ParseError::InvalidCommand(_, command) => {
+                self.message(Message::InvalidCommand(command))
+            }
+            ParseError::TooManyArguments(_, args) => {
+                self.message(Message::TooManyArguments(&args.join(" ")))
+            }
+            ParseError::TooManyArgumentsForOption(_, idx, args) => self.message(
+                Message::TooManyArgumentsForOption(&args.join(" "), &flags(idx)),
+            ),
+            ParseError::MissingRequiredArguments(_, args) => {
+                self.message(Message::MissingRequiredArguments(&args.join(" ")))
+            }
+            ParseError::MissingRequiredOptions(_, opts) => {
+                self.message(Message::MissingRequiredOptions(&opts.join(", ")))
+            }
+            ParseError::MissingRequiredArgumentsForOption(_, idx, args) => self.message(
+                Message::MissingRequiredArgumentsForOption(&flags(idx), &args.join(" ")),
+            ),
+            ParseError::TooFewValues(_, arg, min, count) => {
+                self.message(Message::TooFewValues(arg, *min, *count))
+            }
+            ParseError::TooManyValues(_, arg, max, count) => {
+                self.message(Message::TooManyValues(arg, *max, *count))
+            }
+            ParseError::InvalidChoice(_, arg, value, _) => {
+                self.message(Message::InvalidChoice(value, arg))
+            }
+            ParseError::InvalidMapEntry(_, idx, entry) => {
+                self.message(Message::InvalidMapEntry(entry, &flags(idx)))
+            }
+            ParseError::InvalidOptionValue(_, idx, value) => {
+                self.message(Message::InvalidOptionValue(value, &flags(idx)))
+            }
+            ParseError::DuplicateMapKey(_, idx, key) => {
+                self.message(Message::DuplicateMapKey(key, &flags(idx)))
+            }
+            ParseError::InvalidOptionFlag(_, flag) => {
+                self.message(Message::InvalidOptionFlag(flag))
+            }
+            ParseError::InvalidHelpFormat(_, Some(format)) => {
+                self.message(Message::InvalidHelpFormat(format))
+            }
+            ParseError::InvalidHelpFormat(_, None) => self.message(Message::MissingHelpFormat),
+        }
+    }
+    // `Did you mean X?`, empty when there is nothing to suggest
+    pub(crate) fn did_you_mean(&self, similar: &[String]) -> String {
+        match similar {
+            [] => String::new(),
+            [suggestion] => self.message(Message::DidYouMean(suggestion)),
+            _ => self.message(Message::DidYouMeanOneOf(&similar.join(", "))),
+        }
+    }
+    // Short and long flags of the used command, e.g. (`-f`, `--force`)
+    pub(crate) fn used_command_flags(&self) -> (Vec<String>, Vec<String>) {
+        let used_command = self.used_command.as_ref().unwrap();
+        let command_def = self
+            .commands
+            .iter()
+            .find(|cmd| &cmd.name == used_command)
+            .unwrap();
+
+        let mut long_flags = Vec::new();
+        let mut short_flags = Vec::new();
+
+        // Hidden options and old spellings still work, but shouldn't be suggested
+        for opt in command_def.options.iter().filter(|opt| !opt.is_hidden()) {
+            match &opt.flags {
+                CliOptionFlags::Short(s) => {
+                    short_flags.push(format!("-{}", s));
+                }
+                CliOptionFlags::Long(l) => {
+                    long_flags.push(format!("--{}", l));
+                }
+                CliOptionFlags::ShortAndLong(s, l) => {
+                    short_flags.push(format!("-{}", s));
+                    long_flags.push(format!("--{}", l));
                 }
-                println!(
-                    "( For more help on options run: `{} help {}` )",
-                    self.executable_name,
-                    self.used_command.as_ref().unwrap()
-                );
             }
         }
-        println!();
+
+        (short_flags, long_flags)
+    }
+    // Option errors refer to the option by its index in the used command
+    fn option_def(&self, idx: usize) -> &CliOption {
+        let used_command = self.used_command.as_ref().unwrap();
+        let command_def = self
+            .commands
+            .iter()
+            .find(|cmd| &cmd.name == used_command)
+            .unwrap();
+
+        &command_def.options[idx]
     }
 }
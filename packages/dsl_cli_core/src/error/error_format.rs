@@ -0,0 +1,23 @@
+// Overrides `Cli::set_error_format`, `json` or `text`
+pub const ERROR_FORMAT_ENV: &str = "DSL_CLI_ERROR_FORMAT";
+
+// How `handle_parse_error` reports errors
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    // The message, the marked command line and tips, for humans
+    #[default]
+    Text,
+    // A single JSON object on stderr, see `ErrorReport`
+    Json,
+}
+
+impl ErrorFormat {
+    // Parses the value of `DSL_CLI_ERROR_FORMAT`
+    pub fn from_env_value(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "text" => Some(ErrorFormat::Text),
+            "json" => Some(ErrorFormat::Json),
+            _ => None,
+        }
+    }
+}
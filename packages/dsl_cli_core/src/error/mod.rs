@@ -1,6 +1,11 @@
 pub mod cli;
+mod error_format;
 mod parse_error;
+mod report;
+mod snippet;
 mod suggest_similar;
 
-pub use parse_error::ParseError;
-use suggest_similar::suggest_similar;
+pub use error_format::{ERROR_FORMAT_ENV, ErrorFormat};
+pub use parse_error::{ParseError, ParseErrorKind};
+pub use report::ErrorReport;
+pub(crate) use suggest_similar::{is_similar, similar_candidates};
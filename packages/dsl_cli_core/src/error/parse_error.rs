@@ -1,9 +1,104 @@
+// The first field of every variant is the index of the offending token in the command line.
+// Errors about something missing point one past the last token.
 #[derive(Debug)]
 pub enum ParseError {
-    InvalidCommand(String),
-    TooManyArguments(Vec<String>),
-    MissingRequiredArguments(Vec<String>),
-    MissingRequiredOptions(Vec<String>),
-    MissingRequiredArgumentsForOption(usize, Vec<String>), // index of the option, arguments
-    InvalidOptionFlag(String),
+    InvalidCommand(usize, String),
+    TooManyArguments(usize, Vec<String>),
+    TooManyArgumentsForOption(usize, usize, Vec<String>), // token, index of the option, extra arguments
+    MissingRequiredArguments(usize, Vec<String>),
+    MissingRequiredOptions(usize, Vec<String>),
+    MissingRequiredArgumentsForOption(usize, usize, Vec<String>), // token, index of the option, arguments
+    InvalidOptionFlag(usize, String),
+    TooFewValues(usize, String, usize, usize), // token, argument, minimum, number of values provided
+    TooManyValues(usize, String, usize, usize), // token, argument, maximum, number of values provided
+    InvalidMapEntry(usize, usize, String), // token, index of the option, entry without a `=` or that doesn't parse
+    InvalidOptionValue(usize, usize, String), // token, index of the option, tuple or array value that doesn't parse
+    DuplicateMapKey(usize, usize, String),    // token, index of the option, key
+    InvalidChoice(usize, String, String, Vec<String>), // token, argument, value, possible values
+    InvalidHelpFormat(usize, Option<String>), // token, value of `help --format`, `None` when missing
+}
+
+impl ParseError {
+    pub fn kind(&self) -> ParseErrorKind {
+        match self {
+            ParseError::InvalidCommand(..) => ParseErrorKind::InvalidCommand,
+            ParseError::TooManyArguments(..) => ParseErrorKind::TooManyArguments,
+            ParseError::TooManyArgumentsForOption(..) => ParseErrorKind::TooManyArgumentsForOption,
+            ParseError::MissingRequiredArguments(..) => ParseErrorKind::MissingRequiredArguments,
+            ParseError::MissingRequiredOptions(..) => ParseErrorKind::MissingRequiredOptions,
+            ParseError::MissingRequiredArgumentsForOption(..) => {
+                ParseErrorKind::MissingRequiredArgumentsForOption
+            }
+            ParseError::InvalidOptionFlag(..) => ParseErrorKind::InvalidOptionFlag,
+            ParseError::TooFewValues(..) => ParseErrorKind::TooFewValues,
+            ParseError::TooManyValues(..) => ParseErrorKind::TooManyValues,
+            ParseError::InvalidMapEntry(..) => ParseErrorKind::InvalidMapEntry,
+            ParseError::InvalidOptionValue(..) => ParseErrorKind::InvalidOptionValue,
+            ParseError::DuplicateMapKey(..) => ParseErrorKind::DuplicateMapKey,
+            ParseError::InvalidChoice(..) => ParseErrorKind::InvalidChoice,
+            ParseError::InvalidHelpFormat(..) => ParseErrorKind::InvalidHelpFormat,
+        }
+    }
+    // Index of the offending token in the command line
+    pub fn token_index(&self) -> usize {
+        match self {
+            ParseError::InvalidCommand(token, ..)
+            | ParseError::TooManyArguments(token, ..)
+            | ParseError::TooManyArgumentsForOption(token, ..)
+            | ParseError::MissingRequiredArguments(token, ..)
+            | ParseError::MissingRequiredOptions(token, ..)
+            | ParseError::MissingRequiredArgumentsForOption(token, ..)
+            | ParseError::InvalidOptionFlag(token, ..)
+            | ParseError::TooFewValues(token, ..)
+            | ParseError::TooManyValues(token, ..)
+            | ParseError::InvalidMapEntry(token, ..)
+            | ParseError::InvalidOptionValue(token, ..)
+            | ParseError::DuplicateMapKey(token, ..)
+            | ParseError::InvalidChoice(token, ..)
+            | ParseError::InvalidHelpFormat(token, ..) => *token,
+        }
+    }
+}
+
+// The variants of `ParseError` without their data, e.g. to set exit codes per kind
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseErrorKind {
+    InvalidCommand,
+    TooManyArguments,
+    TooManyArgumentsForOption,
+    MissingRequiredArguments,
+    MissingRequiredOptions,
+    MissingRequiredArgumentsForOption,
+    InvalidOptionFlag,
+    TooFewValues,
+    TooManyValues,
+    InvalidMapEntry,
+    InvalidOptionValue,
+    DuplicateMapKey,
+    InvalidChoice,
+    InvalidHelpFormat,
+}
+
+impl ParseErrorKind {
+    // Stable snake_case name, used in JSON error output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ParseErrorKind::InvalidCommand => "invalid_command",
+            ParseErrorKind::TooManyArguments => "too_many_arguments",
+            ParseErrorKind::TooManyArgumentsForOption => "too_many_arguments_for_option",
+            ParseErrorKind::MissingRequiredArguments => "missing_required_arguments",
+            ParseErrorKind::MissingRequiredOptions => "missing_required_options",
+            ParseErrorKind::MissingRequiredArgumentsForOption => {
+                "missing_required_arguments_for_option"
+            }
+            ParseErrorKind::InvalidOptionFlag => "invalid_option_flag",
+            ParseErrorKind::TooFewValues => "too_few_values",
+            ParseErrorKind::TooManyValues => "too_many_values",
+            ParseErrorKind::InvalidMapEntry => "invalid_map_entry",
+            ParseErrorKind::InvalidOptionValue => "invalid_option_value",
+            ParseErrorKind::DuplicateMapKey => "duplicate_map_key",
+            ParseErrorKind::InvalidChoice => "invalid_choice",
+            ParseErrorKind::InvalidHelpFormat => "invalid_help_format",
+        }
+    }
 }
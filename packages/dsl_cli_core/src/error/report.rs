@@ -0,0 +1,131 @@
+use crate::{
+    Cli,
+    error::{ERROR_FORMAT_ENV, ErrorFormat, ParseError, similar_candidates},
+    help::json::HELP_FORMATS,
+    json::Json,
+};
+
+// Everything known about a parse error, in a form that can be serialized for tooling
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorReport {
+    // e.g. `invalid_option_flag`, see `ParseErrorKind::as_str`
+    pub kind: String,
+    pub message: String,
+    // The offending token, `None` when something is missing at the end of the command line. For a
+    // value read from an environment variable, that value
+    pub token: Option<String>,
+    pub token_index: usize,
+    // e.g. `app push`, just `app` when no command was matched
+    pub command_path: String,
+    // Likely meant commands, flags or values
+    pub suggestions: Vec<String>,
+    pub exit_code: i32,
+}
+
+impl ErrorReport {
+    // A single line JSON object
+    pub fn to_json(&self) -> String {
+        Json::object([
+            ("kind", Json::string(&self.kind)),
+            ("message", Json::string(&self.message)),
+            ("token", Json::optional_string(self.token.as_ref())),
+            ("token_index", Json::Number(self.token_index)),
+            ("command_path", Json::string(&self.command_path)),
+            (
+                "suggestions",
+                Json::Array(self.suggestions.iter().map(Json::string).collect()),
+            ),
+            ("exit_code", Json::Integer(self.exit_code.into())),
+        ])
+        .to_string()
+    }
+}
+
+impl Cli {
+    pub fn error_report(&self, e: &ParseError) -> ErrorReport {
+        let command_path = match self.used_command.as_deref() {
+            Some("cli") | None => self.executable_name.clone(),
+            Some(command) => format!("{} {}", self.executable_name, command),
+        };
+
+        let mut suggestions = self.typo_suggestions(e);
+        suggestions.extend(self.command_suggestions());
+
+        ErrorReport {
+            kind: e.kind().as_str().to_owned(),
+            message: self.error_message(e),
+            token: self.offending_token(e.token_index()),
+            token_index: e.token_index(),
+            command_path,
+            suggestions,
+            exit_code: self.exit_code(e),
+        }
+    }
+    // The token at `token_idx`, indexes past the command line are environment variable values
+    fn offending_token(&self, token_idx: usize) -> Option<String> {
+        match token_idx.checked_sub(self.invocation.len() + 1) {
+            Some(env_idx) => self.env_values.get(env_idx).map(|(_, value)| value.clone()),
+            None => self.invocation.get(token_idx).cloned(),
+        }
+    }
+    // `DSL_CLI_ERROR_FORMAT` takes priority over the format set on the Cli
+    pub(crate) fn error_format(&self) -> ErrorFormat {
+        std::env::var(ERROR_FORMAT_ENV)
+            .ok()
+            .and_then(|value| ErrorFormat::from_env_value(&value))
+            .unwrap_or(self.error_format)
+    }
+    // The per-kind override if there is one, otherwise the policy's usage error code
+    pub fn exit_code(&self, e: &ParseError) -> i32 {
+        self.exit_code_overrides
+            .get(&e.kind())
+            .copied()
+            .unwrap_or(self.exit_code_policy.usage_error())
+    }
+
+    // What the offending token was probably meant to be
+    pub(crate) fn typo_suggestions(&self, e: &ParseError) -> Vec<String> {
+        match e {
+            ParseError::InvalidCommand(_, command) => {
+                similar_candidates(command.clone(), self.command_names())
+            }
+            ParseError::InvalidChoice(_, _, value, choices) => {
+                similar_candidates(value.clone(), choices.clone())
+            }
+            ParseError::InvalidHelpFormat(_, Some(format)) => similar_candidates(
+                format.clone(),
+                HELP_FORMATS
+                    .iter()
+                    .map(|format| format.to_string())
+                    .collect(),
+            ),
+            ParseError::InvalidOptionFlag(_, flag) if flag.starts_with("--") => {
+                similar_candidates(flag.clone(), self.used_command_flags().1)
+            }
+            ParseError::InvalidOptionFlag(_, flag) => {
+                // Single letters are too short for edit distance, only the case can be off
+                let swapped_case = flag
+                    .chars()
+                    .map(|c| match c.is_uppercase() {
+                        true => c.to_ascii_lowercase(),
+                        false => c.to_ascii_uppercase(),
+                    })
+                    .collect::<String>();
+                match self.used_command_flags().0.contains(&swapped_case) {
+                    true => vec![swapped_case],
+                    false => Vec::new(),
+                }
+            }
+            _ => Vec::new(),
+        }
+    }
+    // A mistyped command falls back to `cli`, the commands that were probably meant
+    pub(crate) fn command_suggestions(&self) -> Vec<String> {
+        match (&self.used_command, &self.unmatched_command) {
+            (Some(used_command), Some(token)) if used_command == "cli" => {
+                similar_candidates(token.clone(), self.command_names())
+            }
+            _ => Vec::new(),
+        }
+    }
+}
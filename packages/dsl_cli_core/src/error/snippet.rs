@@ -0,0 +1,80 @@
+use crate::{Cli, help::layout::display_width, locale::Message, style::Stream};
+
+impl Cli {
+    // The command line with a `^^^` marker under the token at `token_idx`, followed by the usage
+    // of the command, e.g.
+    //
+    //   |
+    //   | app push origin --forse
+    //   |                 ^^^^^^^
+    //   |
+    //   = usage: app push <remote> [options]
+    //
+    // A value read from an environment variable is shown as an assignment before the command,
+    // e.g. `PORT=abc app serve`, with the marker under it.
+    pub(crate) fn error_snippet(&self, token_idx: usize) -> Vec<String> {
+        let theme = self.theme_for(Stream::Stderr);
+
+        let env_value = token_idx
+            .checked_sub(self.invocation.len() + 1)
+            .and_then(|env_idx| self.env_values.get(env_idx));
+        let words = env_value
+            .map(|(env, value)| format!("{}={}", env, quote_token(value)))
+            .into_iter()
+            .chain(std::iter::once(self.executable_name.clone()))
+            .chain(self.invocation.iter().map(|token| quote_token(token)))
+            .collect::<Vec<String>>();
+        // Position of the marked word, the executable name comes first
+        let position = match env_value {
+            Some(_) => 0,
+            None => token_idx + 1,
+        };
+
+        // Everything before the word, plus the space separating it
+        let offset = words
+            .iter()
+            .take(position)
+            .map(|word| display_width(word) + 1)
+            .sum::<usize>();
+        // Missing values point right after the last token
+        let width = words
+            .get(position)
+            .map(|word| display_width(word))
+            .unwrap_or(1)
+            .max(1);
+
+        let command_line = words.join(" ");
+        let usage = match &self.builtin_usage {
+            Some(usage) => usage.clone(),
+            None => {
+                self.short_help_doc(self.used_command.as_deref().unwrap_or("cli"))
+                    .usage
+            }
+        };
+
+        vec![
+            "  |".to_owned(),
+            format!("  | {}", command_line),
+            format!(
+                "  | {}{}",
+                " ".repeat(offset),
+                theme.error.paint(&"^".repeat(width))
+            ),
+            "  |".to_owned(),
+            format!("  = {} {}", self.message(Message::UsageNote), usage),
+        ]
+    }
+}
+
+// Quotes tokens the way a shell would need them, so the line can be copied back
+fn quote_token(token: &str) -> String {
+    let plain = !token.is_empty()
+        && token
+            .chars()
+            .all(|c| c.is_alphanumeric() || "-_=.,:/@%+~".contains(c));
+    if plain {
+        token.to_owned()
+    } else {
+        format!("'{}'", token.replace('\'', "'\\''"))
+    }
+}
@@ -1,11 +1,14 @@
 use std::collections::HashSet;
 
 const MAX_DISTANCE: usize = 3;
+const MIN_SIMILARITY: f64 = 0.4;
 
 fn edit_distance(a: &str, b: &str) -> usize {
     // https://en.wikipedia.org/wiki/Damerau–Levenshtein_distance
     // Calculating optimal string alignment distance, no substring is edited more than once.
     // (Simple implementation.)
+    let a = a.chars().collect::<Vec<char>>();
+    let b = b.chars().collect::<Vec<char>>();
 
     // Quick early exit, return worst case.
     if a.len().abs_diff(b.len()) > MAX_DISTANCE {
@@ -16,22 +19,18 @@ fn edit_distance(a: &str, b: &str) -> usize {
     let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
 
     // pure deletions turn a into empty string
-    for i in 0..=a.len() {
-        d[i][0] = i;
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
     }
     // pure insertions turn empty string into b
-    for j in 0..=b.len() {
-        d[0][j] = j;
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
     }
 
     // fill matrix
     for j in 1..=b.len() {
         for i in 1..=a.len() {
-            let cost = if a.chars().nth(i - 1).unwrap() == b.chars().nth(j - 1).unwrap() {
-                0
-            } else {
-                1
-            };
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
 
             let deletion = d[i - 1][j] + 1;
             let insertion = d[i][j - 1] + 1;
@@ -40,11 +39,7 @@ fn edit_distance(a: &str, b: &str) -> usize {
             d[i][j] = deletion.min(insertion).min(substitution);
 
             // transposition
-            if i > 1
-                && j > 1
-                && a.chars().nth(i - 1).unwrap() == b.chars().nth(j - 2).unwrap()
-                && a.chars().nth(i - 2).unwrap() == b.chars().nth(j - 1).unwrap()
-            {
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
                 d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
             }
         }
@@ -53,20 +48,21 @@ fn edit_distance(a: &str, b: &str) -> usize {
     d[a.len()][b.len()]
 }
 
-///
-/// Find close matches, restricted to same number of edits.
-///
-/// # Arguments
-///
-/// * `word` - The word to suggest similar words for.
-/// * `candidates` - The candidates to suggest similar words from.
-///
-/// # Returns
-///
-/// The suggested similar words.
-pub fn suggest_similar(word: String, candidates: Vec<String>) -> String {
+// Whether `word` is close enough to `candidate` to be a typo of it, within `max_distance` edits
+pub(crate) fn is_similar(word: &str, candidate: &str, max_distance: usize) -> bool {
+    let distance = edit_distance(word, candidate);
+    let length = word.chars().count().max(candidate.chars().count());
+    distance <= max_distance.min(MAX_DISTANCE) && similarity(length, distance) > MIN_SIMILARITY
+}
+
+fn similarity(length: usize, distance: usize) -> f64 {
+    length.saturating_sub(distance) as f64 / length as f64
+}
+
+// The candidates with the smallest edit distance to `word`, sorted
+pub(crate) fn similar_candidates(word: String, candidates: Vec<String>) -> Vec<String> {
     if candidates.is_empty() {
-        return String::new();
+        return Vec::new();
     }
 
     let mut word = word.clone();
@@ -90,16 +86,14 @@ pub fn suggest_similar(word: String, candidates: Vec<String>) -> String {
 
     let mut similar = Vec::new();
     let mut best_distance = MAX_DISTANCE;
-    let min_similarity = 0.4;
     for candidate in candidates {
         if candidate.len() <= 1 {
             continue; // no one character guesses
         }
 
         let distance = edit_distance(&word, &candidate);
-        let length = word.len().max(candidate.len());
-        let similarity = (length - distance) as f64 / length as f64;
-        if similarity > min_similarity {
+        let length = word.chars().count().max(candidate.chars().count());
+        if similarity(length, distance) > MIN_SIMILARITY {
             if distance < best_distance {
                 // better edit distance, throw away previous worse matches
                 best_distance = distance;
@@ -118,11 +112,5 @@ pub fn suggest_similar(word: String, candidates: Vec<String>) -> String {
             .collect();
     }
 
-    if similar.len() > 1 {
-        format!("Did you mean one of {}?", similar.join(", "))
-    } else if similar.len() == 1 {
-        format!("Did you mean {}?", similar[0])
-    } else {
-        String::new()
-    }
+    similar
 }
@@ -0,0 +1,60 @@
+use std::fmt::Display;
+
+use crate::{Cli, locale::Message, style::Stream};
+
+// Exit codes from BSD's sysexits.h, for applications that follow it
+pub const EX_OK: i32 = 0;
+pub const EX_USAGE: i32 = 64;
+pub const EX_DATAERR: i32 = 65;
+pub const EX_NOINPUT: i32 = 66;
+pub const EX_UNAVAILABLE: i32 = 69;
+pub const EX_SOFTWARE: i32 = 70;
+pub const EX_IOERR: i32 = 74;
+pub const EX_TEMPFAIL: i32 = 75;
+pub const EX_NOPERM: i32 = 77;
+pub const EX_CONFIG: i32 = 78;
+
+// Exit code of runtime failures, by convention. Usage errors never use it, so wrapper scripts
+// can tell them apart.
+pub const EXIT_FAILURE: i32 = 1;
+
+// The exit code of parse errors, help always exits with 0
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExitCodePolicy {
+    // 2, like most GNU tools
+    #[default]
+    Gnu,
+    // 64 (`EX_USAGE`)
+    Sysexits,
+}
+
+impl ExitCodePolicy {
+    pub fn usage_error(&self) -> i32 {
+        match self {
+            ExitCodePolicy::Gnu => 2,
+            ExitCodePolicy::Sysexits => EX_USAGE,
+        }
+    }
+}
+
+// Ends the application: exits with 0 on `Ok`, prints `error: <err>` to the stderr of `cli` (with
+// its colors and translations) and exits with `exit_code(&err)` otherwise. Meant for the end of
+// `main`, e.g. `exit_with(&build_cli(), run(command), |_| EXIT_FAILURE)`
+pub fn exit_with<E: Display>(
+    cli: &Cli,
+    result: Result<(), E>,
+    exit_code: impl FnOnce(&E) -> i32,
+) -> ! {
+    match result {
+        Ok(()) => std::process::exit(EX_OK),
+        Err(err) => {
+            let theme = cli.theme_for(Stream::Stderr);
+            let label = cli.message(Message::ErrorLabel);
+            cli.write_to(
+                Stream::Stderr,
+                &format!("{} {}\n", theme.error.paint(&label), err),
+            );
+            std::process::exit(exit_code(&err));
+        }
+    }
+}
@@ -1,10 +1,19 @@
-use crate::{Cli, CliArgument, CliCommand, CliOption};
+use crate::{CliArgument, help::HelpEntry};
 
 impl CliArgument {
+    // Takes in an argument and returns an entry named something like: <name...>
+    pub fn help_entry(&self) -> HelpEntry {
+        HelpEntry {
+            required: !self.optional,
+            default: self.default.clone(),
+            choices: self.choices.clone(),
+            deprecated: self.deprecated.clone(),
+            ..HelpEntry::new(self.reconstruct_name(), self.description.clone())
+        }
+    }
     // Takes in an argument an returns something like: (<name...>,description)
+    #[deprecated(since = "0.3.0", note = "use `help_entry()` instead")]
     pub fn info(&self) -> (String, String) {
-        let name = self.reconstruct_name();
-        let description = self.description.clone().unwrap_or_default();
-        (name, description)
+        self.help_entry().into_pair()
     }
 }
@@ -1,122 +1,299 @@
-use crate::{Cli, CliCommand};
+use crate::{
+    Cli, CliArgument, CliCommand, CliOption,
+    help::{
+        HelpDoc, HelpEntry, HelpExample, HelpOrder, HelpRenderer, HelpSection, HelpSectionKind,
+        PlainTextRenderer,
+        layout::{HelpLayout, help_width},
+    },
+    locale::Message,
+    style::Stream,
+    types::HelpText,
+};
 
 impl Cli {
+    // The long help, shown by `help [command]` and `--help`
     pub fn show_help(&self, command_name: String) {
-        // This might not exist if the command name is cli. Otherwise it will be a valid command.
-        let cmd_def = self.commands.iter().find(|cmd| cmd.name == command_name);
+        self.print_help_doc(&self.help_doc(&command_name));
+    }
+    // The short help, shown by `-h`
+    pub fn show_short_help(&self, command_name: String) {
+        self.print_help_doc(&self.short_help_doc(&command_name));
+    }
+    // Every page in one document, shown by `help --all`
+    pub fn show_all_help(&self) {
+        let text = std::iter::once("cli")
+            .chain(
+                self.commands
+                    .iter()
+                    .filter(|cmd| self.is_listed(cmd.is_hidden()))
+                    .map(|cmd| cmd.name.as_str())
+                    .filter(|name| *name != "cli"),
+            )
+            .map(|name| self.render_help_doc(&self.help_doc(name)))
+            .collect::<String>();
 
-        let mut cmds_info = Vec::new();
-        let mut args_info = Vec::new();
-        let mut opts_info = Vec::new();
+        self.page_or_print(&text);
+    }
+    // Shown by `help search <query>`
+    pub fn show_search_results(&self, query: &str) {
+        let theme = self.theme_for(Stream::Stdout);
+        let results = self.search_help(query);
 
-        match cmd_def {
-            Some(cmd_def) => {
-                args_info = cmd_def.args_info();
-                opts_info = cmd_def.opts_info();
+        if results.is_empty() {
+            let no_results = self.message(Message::NoSearchResults(query));
+            self.write_to(Stream::Stdout, &format!("\n{}\n\n", no_results));
+            return;
+        }
+
+        let layout = HelpLayout::new(
+            help_width(self.help_width),
+            results.iter().map(|result| result.path.as_str()),
+        );
+        let mut lines = vec![
+            String::new(),
+            theme
+                .header
+                .paint(&self.message(Message::SearchResults(query))),
+        ];
+        for result in &results {
+            let styled_path = match result.path.split_once(' ') {
+                Some((cmd, rest)) => {
+                    format!("{} {}", theme.command.paint(cmd), theme.paint_entry(rest))
+                }
+                None => theme.command.paint(&result.path),
+            };
+            for (idx, line) in layout
+                .render_row(&result.path, &result.description)
+                .into_iter()
+                .enumerate()
+            {
+                if idx == 0 {
+                    lines.push(line.replacen(&result.path, &styled_path, 1));
+                } else {
+                    lines.push(line);
+                }
             }
-            None => {}
         }
+        lines.push(String::new());
 
-        if command_name == "cli" {
-            cmds_info = self
-                .commands
-                .iter()
-                .map(|cmd| cmd.info())
-                .collect::<Vec<(String, String)>>();
-            cmds_info.push((
-                String::from("help [command]"),
-                String::from("Show help for a command"),
-            ));
+        self.page_or_print(&(lines.join("\n") + "\n"));
+    }
+    fn print_help_doc(&self, doc: &HelpDoc) {
+        self.page_or_print(&self.render_help_doc(doc));
+    }
+    fn render_help_doc(&self, doc: &HelpDoc) -> String {
+        match &self.help_renderer {
+            Some(renderer) => renderer.render(doc),
+            None => {
+                let mut renderer = PlainTextRenderer::new(
+                    help_width(self.help_width),
+                    self.theme_for(Stream::Stdout),
+                );
+                renderer.set_messages(self.messages()).render(doc)
+            }
         }
+    }
 
-        let args_max_width = args_info
-            .iter()
-            .map(|(name, _)| name.len())
-            .max()
-            .unwrap_or(0);
-        let opts_max_width = opts_info
-            .iter()
-            .map(|(name, _)| name.len())
-            .max()
-            .unwrap_or(0);
-        let cmds_max_width = cmds_info
-            .iter()
-            .map(|(name, _)| name.len())
-            .max()
-            .unwrap_or(0);
-        let max_width = args_max_width.max(opts_max_width).max(cmds_max_width) + 2;
-
-        // Display Usage
-        match cmd_def {
-            Some(cmd_def) => {
-                println!();
-                let mut usage_string = String::new();
-
-                usage_string.push_str(&self.executable_name);
-
-                if command_name != "cli" {
-                    usage_string.push_str(" ");
-                    usage_string.push_str(&command_name);
-                }
+    // Builds the long help page for a command, `cli` for the top-level page
+    pub fn help_doc(&self, command_path: &str) -> HelpDoc {
+        self.build_help_doc(command_path, true)
+    }
+    // Like `help_doc`, but only the first paragraph of descriptions and no examples
+    pub fn short_help_doc(&self, command_path: &str) -> HelpDoc {
+        self.build_help_doc(command_path, false)
+    }
+    fn build_help_doc(&self, command_path: &str, long: bool) -> HelpDoc {
+        // This might not exist if the command name is cli. Otherwise it will be a valid command.
+        let cmd_def = self.commands.iter().find(|cmd| cmd.name == command_path);
 
-                if !args_info.is_empty() {
-                    let args_string = " ".to_owned()
-                        + &args_info
-                            .iter()
-                            .map(|(name, _)| name.as_str())
-                            .collect::<Vec<&str>>()
-                            .join(" ");
-                    usage_string.push_str(&args_string);
-                }
+        let mut sections = Vec::new();
+        let mut usage = format!("{} <command>", self.executable_name);
+        let mut description = None;
+        let mut help_text = HelpText::default();
 
-                if !opts_info.is_empty() {
-                    usage_string.push_str(" [options]");
-                }
+        if let Some(cmd_def) = cmd_def {
+            let arguments = cmd_def
+                .arguments
+                .iter()
+                .filter(|arg| self.is_listed(arg.hidden))
+                .collect::<Vec<&CliArgument>>();
+            let mut options = cmd_def
+                .options
+                .iter()
+                .filter(|opt| self.is_listed(opt.is_hidden()))
+                .collect::<Vec<&CliOption>>();
 
-                println!("Usage: {}", usage_string);
+            let mut usage_string = self.executable_name.clone();
+
+            if command_path != "cli" {
+                usage_string.push(' ');
+                usage_string.push_str(command_path);
             }
-            None => {}
-        }
-        // Display Description
-        match cmd_def {
-            Some(cmd_def) => {
-                let description = &cmd_def.description;
 
-                if let Some(description) = description {
-                    println!("{}", description)
-                } else {
-                    println!("No description available")
+            // Hidden arguments and options that are required still have to be typed, so they
+            // stay in the usage line and are only left out of the sections
+            for arg_def in cmd_def
+                .arguments
+                .iter()
+                .filter(|arg| !arg.optional || self.is_listed(arg.hidden))
+            {
+                usage_string.push(' ');
+                usage_string.push_str(&arg_def.reconstruct_name());
+            }
+
+            // Required options are spelled out, the optional ones are summed up as `[options]`
+            for opt_def in cmd_def.options.iter().filter(|opt| !opt.optional) {
+                usage_string.push(' ');
+                usage_string.push_str(&opt_def.primary_flag());
+                for arg_def in &opt_def.args {
+                    usage_string.push(' ');
+                    usage_string.push_str(&arg_def.reconstruct_name());
                 }
             }
-            None => {}
+
+            if options.iter().any(|opt| opt.optional) {
+                usage_string.push_str(" [options]");
+            }
+
+            usage = usage_string;
+            description = cmd_def.description.clone();
+            help_text = cmd_def.help_text.clone();
+
+            if self.help_order == HelpOrder::Alphabetical {
+                options.sort_by(|a, b| a.name.cmp(&b.name));
+            }
+
+            sections.extend(
+                self.group_sections(
+                    HelpSectionKind::Arguments,
+                    arguments
+                        .iter()
+                        .map(|arg| (arg.section.as_deref(), arg.help_entry())),
+                ),
+            );
+            sections.extend(
+                self.group_sections(
+                    HelpSectionKind::Options,
+                    options
+                        .iter()
+                        .map(|opt| (opt.section.as_deref(), opt.help_entry())),
+                ),
+            );
         }
-        // Display Arguments
-        if !args_info.is_empty() {
-            println!("\nArguments:");
-            for (name, description) in args_info {
-                let width = name.len();
-                let padding = " ".repeat(max_width - width);
-                println!("  {}{}{}", name, padding, description);
+
+        // The top-level page falls back to the text given to the whole CLI. The description and
+        // `long_about` fall back together, so `-h` and `help` describe the same thing
+        if command_path == "cli" {
+            let app_text = &self.help_text;
+            if description.is_none() && help_text.long_about.is_none() {
+                description = Some(self.description.clone());
+                help_text.long_about = app_text.long_about.clone();
             }
+            help_text.before_help = help_text.before_help.or(app_text.before_help.clone());
+            help_text.after_help = help_text.after_help.or(app_text.after_help.clone());
+            help_text
+                .examples
+                .splice(0..0, app_text.examples.iter().cloned());
         }
-        // Display Options
-        if !opts_info.is_empty() {
-            println!("\nOptions:");
-            for (name, description) in opts_info {
-                let width = name.len();
-                let padding = " ".repeat(max_width - width);
-                println!("  {}{}{}", name, padding, description);
+
+        if command_path == "cli" {
+            let mut commands = self
+                .commands
+                .iter()
+                .filter(|cmd| self.is_listed(cmd.is_hidden()))
+                .collect::<Vec<&CliCommand>>();
+            if self.help_order == HelpOrder::Alphabetical {
+                commands.sort_by(|a, b| a.name.cmp(&b.name));
             }
+
+            let mut cmd_entries = commands
+                .iter()
+                .map(|cmd| (cmd.section.as_deref(), cmd.help_entry()))
+                .collect::<Vec<(Option<&str>, HelpEntry)>>();
+            if self.has_completions_command() {
+                cmd_entries.push((
+                    None,
+                    HelpEntry::new(
+                        "completions <shell>",
+                        Some(self.message(Message::CompletionsCommandDescription)),
+                    ),
+                ));
+            }
+            cmd_entries.push((
+                None,
+                HelpEntry::new(
+                    "help [command]",
+                    Some(self.message(Message::HelpCommandDescription)),
+                ),
+            ));
+            sections.extend(self.group_sections(HelpSectionKind::Commands, cmd_entries));
         }
-        // Display Commands
-        if !cmds_info.is_empty() {
-            println!("\nCommands:");
-            for (name, description) in cmds_info {
-                let width = name.len();
-                let padding = " ".repeat(max_width - width);
-                println!("  {}{}{}", name, padding, description);
+
+        let (description, examples) = if long {
+            let examples = help_text
+                .examples
+                .into_iter()
+                .map(|(command, description)| HelpExample {
+                    command,
+                    description,
+                })
+                .collect();
+            (help_text.long_about.or(description), examples)
+        } else {
+            for entry in sections.iter_mut().flat_map(|s| s.entries.iter_mut()) {
+                entry.description = entry.description.as_deref().map(first_paragraph);
             }
+            (description.as_deref().map(first_paragraph), Vec::new())
+        };
+
+        HelpDoc {
+            command: command_path.to_owned(),
+            usage,
+            description,
+            before_help: help_text.before_help,
+            after_help: help_text.after_help,
+            sections,
+            examples,
         }
-        println!();
     }
+    // Hidden items are only listed with `help --hidden`
+    pub(crate) fn is_listed(&self, hidden: bool) -> bool {
+        self.show_hidden || !hidden
+    }
+    // Splits entries by their heading. Entries without one go in the default section, which comes
+    // first, the custom sections follow in the order they were declared. Empty sections are left
+    // out, e.g. the default one when every entry has a heading.
+    fn group_sections<'a>(
+        &self,
+        kind: HelpSectionKind,
+        entries: impl IntoIterator<Item = (Option<&'a str>, HelpEntry)>,
+    ) -> Vec<HelpSection> {
+        let default_title = self.message(Message::SectionTitle(kind));
+        let mut sections = vec![HelpSection::titled(kind, default_title, Vec::new())];
+
+        for (heading, entry) in entries {
+            let idx = match heading {
+                None => 0,
+                Some(heading) => match sections.iter().skip(1).position(|s| s.title == heading) {
+                    Some(idx) => idx + 1,
+                    None => {
+                        sections.push(HelpSection::titled(kind, heading, Vec::new()));
+                        sections.len() - 1
+                    }
+                },
+            };
+            sections[idx].entries.push(entry);
+        }
+
+        sections.retain(|section| !section.entries.is_empty());
+        sections
+    }
+}
+
+fn first_paragraph(text: &str) -> String {
+    text.split("\n\n")
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_owned()
 }
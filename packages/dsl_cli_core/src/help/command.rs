@@ -1,15 +1,34 @@
-use crate::CliCommand;
+use crate::{CliCommand, help::HelpEntry};
 
 impl CliCommand {
+    pub fn arg_entries(&self) -> Vec<HelpEntry> {
+        self.arguments.iter().map(|arg| arg.help_entry()).collect()
+    }
+    pub fn opt_entries(&self) -> Vec<HelpEntry> {
+        self.options.iter().map(|opt| opt.help_entry()).collect()
+    }
+    pub fn help_entry(&self) -> HelpEntry {
+        HelpEntry {
+            deprecated: self.deprecated.clone(),
+            ..HelpEntry::new(self.name.clone(), self.description.clone())
+        }
+    }
+    #[deprecated(since = "0.3.0", note = "use `arg_entries()` instead")]
     pub fn args_info(&self) -> Vec<(String, String)> {
-        self.arguments.iter().map(|arg| arg.info()).collect()
+        self.arg_entries()
+            .into_iter()
+            .map(HelpEntry::into_pair)
+            .collect()
     }
+    #[deprecated(since = "0.3.0", note = "use `opt_entries()` instead")]
     pub fn opts_info(&self) -> Vec<(String, String)> {
-        self.options.iter().map(|opt| opt.info()).collect()
+        self.opt_entries()
+            .into_iter()
+            .map(HelpEntry::into_pair)
+            .collect()
     }
+    #[deprecated(since = "0.3.0", note = "use `help_entry()` instead")]
     pub fn info(&self) -> (String, String) {
-        let name = self.name.clone();
-        let description = self.description.clone().unwrap_or_default();
-        (name, description)
+        self.help_entry().into_pair()
     }
 }
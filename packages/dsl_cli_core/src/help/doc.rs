@@ -0,0 +1,105 @@
+// A renderer-agnostic description of a help page, see `Cli::help_doc`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelpDoc {
+    // The command the page is for, `cli` for the top-level page
+    pub command: String,
+    // e.g. `app convert <inputs...> <output> [options]`
+    pub usage: String,
+    // The long description in the long help, the first paragraph in the short one
+    pub description: Option<String>,
+    pub before_help: Option<String>,
+    pub after_help: Option<String>,
+    pub sections: Vec<HelpSection>,
+    // Empty in the short help
+    pub examples: Vec<HelpExample>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelpExample {
+    // e.g. `app split 'a,b' -s ,`
+    pub command: String,
+    pub description: String,
+}
+
+// How options and commands are ordered in the help, positional arguments always keep their order
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HelpOrder {
+    #[default]
+    Declaration,
+    Alphabetical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpSectionKind {
+    Arguments,
+    Options,
+    Commands,
+}
+
+impl HelpSectionKind {
+    pub fn title(&self) -> &'static str {
+        match self {
+            HelpSectionKind::Arguments => "Arguments",
+            HelpSectionKind::Options => "Options",
+            HelpSectionKind::Commands => "Commands",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelpSection {
+    pub kind: HelpSectionKind,
+    pub title: String,
+    pub entries: Vec<HelpEntry>,
+}
+
+impl HelpSection {
+    pub fn new(kind: HelpSectionKind, entries: Vec<HelpEntry>) -> Self {
+        Self {
+            kind,
+            title: kind.title().to_owned(),
+            entries,
+        }
+    }
+    // A section under a custom heading, e.g. `Networking`
+    pub fn titled(
+        kind: HelpSectionKind,
+        title: impl Into<String>,
+        entries: Vec<HelpEntry>,
+    ) -> Self {
+        Self {
+            kind,
+            title: title.into(),
+            entries,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HelpEntry {
+    // How the entry is written on the command line, e.g. `-f, --format <format>`
+    pub name: String,
+    pub description: Option<String>,
+    pub required: bool,
+    pub default: Option<String>,
+    // Environment variable the value can be read from
+    pub env: Option<String>,
+    // The only values accepted, empty when any value is accepted
+    pub choices: Vec<String>,
+    // Why the entry shouldn't be used anymore, e.g. `use --output instead`
+    pub deprecated: Option<String>,
+}
+
+impl HelpEntry {
+    pub fn new(name: impl Into<String>, description: Option<impl Into<String>>) -> Self {
+        Self {
+            name: name.into(),
+            description: description.map(|d| d.into()),
+            ..Self::default()
+        }
+    }
+    // The (name, description) pair the old `info()` methods returned
+    pub(crate) fn into_pair(self) -> (String, String) {
+        (self.name, self.description.unwrap_or_default())
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{Cli, CliArgument, CliCommand, CliOption, json::Json, types::HelpText};
+
+// Bumped whenever a field of the JSON definition is renamed or removed
+pub const DEFINITION_SCHEMA_VERSION: usize = 1;
+
+// The values `help --format` accepts
+pub(crate) const HELP_FORMATS: [&str; 1] = ["json"];
+
+impl Cli {
+    // The whole CLI definition as JSON, printed by `<exe> help --format json`
+    pub fn definition_json(&self) -> String {
+        Json::object([
+            ("schema_version", Json::Number(DEFINITION_SCHEMA_VERSION)),
+            ("name", Json::string(&self.name)),
+            ("version", Json::string(&self.version)),
+            ("description", Json::string(&self.description)),
+            ("executable", Json::string(&self.executable_name)),
+            ("help_text", self.help_text.to_json()),
+            (
+                "commands",
+                Json::Array(self.commands.iter().map(|cmd| cmd.to_json()).collect()),
+            ),
+        ])
+        .to_pretty_string()
+    }
+}
+
+impl CliCommand {
+    pub(crate) fn to_json(&self) -> Json {
+        Json::object([
+            ("name", Json::string(&self.name)),
+            (
+                "description",
+                Json::optional_string(self.description.as_ref()),
+            ),
+            ("section", Json::optional_string(self.section.as_ref())),
+            ("hidden", Json::Bool(self.hidden)),
+            (
+                "deprecated",
+                Json::optional_string(self.deprecated.as_ref()),
+            ),
+            (
+                "replaced_by",
+                Json::optional_string(self.replaced_by.as_ref()),
+            ),
+            ("help_text", self.help_text.to_json()),
+            (
+                "arguments",
+                Json::Array(self.arguments.iter().map(|arg| arg.to_json()).collect()),
+            ),
+            (
+                "options",
+                Json::Array(self.options.iter().map(|opt| opt.to_json()).collect()),
+            ),
+        ])
+    }
+}
+
+impl CliOption {
+    pub(crate) fn to_json(&self) -> Json {
+        let [short, long] = self.flags.values();
+
+        Json::object([
+            ("name", Json::string(&self.name)),
+            ("short", Json::optional_string(short)),
+            ("long", Json::optional_string(long)),
+            (
+                "description",
+                Json::optional_string(self.description.as_ref()),
+            ),
+            ("section", Json::optional_string(self.section.as_ref())),
+            ("required", Json::Bool(!self.optional)),
+            ("env", Json::optional_string(self.env.as_ref())),
+            ("hidden", Json::Bool(self.hidden)),
+            (
+                "deprecated",
+                Json::optional_string(self.deprecated.as_ref()),
+            ),
+            (
+                "replaced_by",
+                Json::optional_string(self.replaced_by.as_ref()),
+            ),
+            (
+                "arguments",
+                Json::Array(self.args.iter().map(|arg| arg.to_json()).collect()),
+            ),
+        ])
+    }
+}
+
+impl CliArgument {
+    pub(crate) fn to_json(&self) -> Json {
+        Json::object([
+            ("name", Json::string(&self.name)),
+            (
+                "description",
+                Json::optional_string(self.description.as_ref()),
+            ),
+            ("type", Json::optional_string(self.type_name.as_ref())),
+            (
+                "value_name",
+                Json::optional_string(self.value_name.as_ref()),
+            ),
+            ("required", Json::Bool(!self.optional)),
+            ("variadic", Json::Bool(self.variadic)),
+            ("map", Json::Bool(self.map)),
+            (
+                "delimiter",
+                Json::optional_string(self.delimiter.map(String::from)),
+            ),
+            ("arity", Json::optional_number(self.arity)),
+            ("min_values", Json::optional_number(self.min_values)),
+            ("max_values", Json::optional_number(self.max_values)),
+            ("default", Json::optional_string(self.default.as_ref())),
+            ("section", Json::optional_string(self.section.as_ref())),
+            ("hidden", Json::Bool(self.hidden)),
+            (
+                "deprecated",
+                Json::optional_string(self.deprecated.as_ref()),
+            ),
+            (
+                "choices",
+                Json::Array(self.choices.iter().map(Json::string).collect()),
+            ),
+        ])
+    }
+}
+
+impl HelpText {
+    pub(crate) fn to_json(&self) -> Json {
+        Json::object([
+            (
+                "long_about",
+                Json::optional_string(self.long_about.as_ref()),
+            ),
+            (
+                "before_help",
+                Json::optional_string(self.before_help.as_ref()),
+            ),
+            (
+                "after_help",
+                Json::optional_string(self.after_help.as_ref()),
+            ),
+            (
+                "examples",
+                Json::Array(
+                    self.examples
+                        .iter()
+                        .map(|(command, description)| {
+                            Json::object([
+                                ("command", Json::string(command)),
+                                ("description", Json::string(description)),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+}
@@ -0,0 +1,169 @@
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+// Overrides the detected terminal width, e.g. `DSL_CLI_HELP_WIDTH=100`
+pub const HELP_WIDTH_ENV: &str = "DSL_CLI_HELP_WIDTH";
+
+const DEFAULT_WIDTH: usize = 80;
+const INDENT: usize = 2;
+const COLUMN_GAP: usize = 2;
+// Below this many columns for descriptions, entries are stacked instead of aligned
+const MIN_DESCRIPTION_WIDTH: usize = 24;
+const STACKED_INDENT: usize = 8;
+
+// Resolves the help width: explicit setting, then env override, then the terminal, then 80
+pub fn help_width(fixed_width: Option<usize>) -> usize {
+    if let Some(width) = fixed_width {
+        return width;
+    }
+
+    if let Some(width) = std::env::var(HELP_WIDTH_ENV)
+        .ok()
+        .and_then(|width| width.trim().parse().ok())
+    {
+        return width;
+    }
+
+    if let Some((terminal_size::Width(width), _)) = terminal_size::terminal_size() {
+        return width as usize;
+    }
+
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|width| width.trim().parse().ok())
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+pub fn display_width(text: &str) -> usize {
+    UnicodeWidthStr::width(text)
+}
+
+// Greedy word wrapping by display width. Words longer than the width are split.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut line_width = 0;
+
+        for word in paragraph.split_whitespace() {
+            let word_width = display_width(word);
+
+            if line_width > 0 && line_width + 1 + word_width <= width {
+                line.push(' ');
+                line.push_str(word);
+                line_width += 1 + word_width;
+                continue;
+            }
+
+            if line_width > 0 {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+            }
+
+            if word_width <= width {
+                line.push_str(word);
+                line_width = word_width;
+                continue;
+            }
+
+            for c in word.chars() {
+                let char_width = c.width().unwrap_or(0);
+                if line_width + char_width > width && line_width > 0 {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0;
+                }
+                line.push(c);
+                line_width += char_width;
+            }
+        }
+
+        lines.push(line);
+    }
+
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+
+    lines
+}
+
+// Lays out `name  description` rows so descriptions line up in a column and wrap under a
+// hanging indent. Falls back to a stacked layout when there's no room for the column.
+pub struct HelpLayout {
+    width: usize,
+    name_width: usize,
+    stacked: bool,
+}
+
+impl HelpLayout {
+    pub fn new<'a>(width: usize, names: impl IntoIterator<Item = &'a str>) -> Self {
+        let name_width = names.into_iter().map(display_width).max().unwrap_or(0);
+        let description_width = width.saturating_sub(INDENT + name_width + COLUMN_GAP);
+
+        Self {
+            width,
+            name_width,
+            stacked: description_width < MIN_DESCRIPTION_WIDTH,
+        }
+    }
+
+    pub fn render_row(&self, name: &str, description: &str) -> Vec<String> {
+        let indent = " ".repeat(INDENT);
+
+        if description.is_empty() {
+            return vec![format!("{}{}", indent, name)];
+        }
+
+        if self.stacked {
+            let description_indent = " ".repeat(STACKED_INDENT);
+            let mut lines = vec![format!("{}{}", indent, name)];
+            lines.extend(
+                wrap(description, self.width.saturating_sub(STACKED_INDENT))
+                    .into_iter()
+                    .map(|line| indent_line(&description_indent, line)),
+            );
+            return lines;
+        }
+
+        let description_column = INDENT + self.name_width + COLUMN_GAP;
+        let padding = " ".repeat(self.name_width - display_width(name) + COLUMN_GAP);
+        let hanging_indent = " ".repeat(description_column);
+
+        wrap(description, self.width - description_column)
+            .into_iter()
+            .enumerate()
+            .map(|(idx, line)| {
+                if idx == 0 {
+                    format!("{}{}{}{}", indent, name, padding, line)
+                } else {
+                    indent_line(&hanging_indent, line)
+                }
+            })
+            .collect()
+    }
+
+    // Wraps a paragraph to the full width, continuation lines are indented by `hanging_indent`
+    pub fn render_paragraph(&self, text: &str, hanging_indent: usize) -> Vec<String> {
+        let mut lines = wrap(text, self.width);
+        if lines.len() > 1 && hanging_indent > 0 {
+            let rest = lines.split_off(1).join(" ");
+            let indent = " ".repeat(hanging_indent);
+            lines.extend(
+                wrap(&rest, self.width.saturating_sub(hanging_indent))
+                    .into_iter()
+                    .map(|line| indent_line(&indent, line)),
+            );
+        }
+        lines
+    }
+}
+
+// Blank lines between paragraphs stay empty instead of holding only the indent
+fn indent_line(indent: &str, line: String) -> String {
+    if line.is_empty() {
+        line
+    } else {
+        format!("{}{}", indent, line)
+    }
+}
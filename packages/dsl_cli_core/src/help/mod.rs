@@ -1,4 +1,16 @@
 pub mod argument;
 pub mod cli;
 pub mod command;
+pub mod doc;
+pub mod json;
+pub mod layout;
 pub mod option;
+pub mod pager;
+pub mod renderer;
+pub mod search;
+
+pub use doc::{HelpDoc, HelpEntry, HelpExample, HelpOrder, HelpSection, HelpSectionKind};
+pub use json::DEFINITION_SCHEMA_VERSION;
+pub use pager::NO_PAGER_ENV;
+pub use renderer::{HelpRenderer, PlainTextRenderer};
+pub use search::SearchResult;
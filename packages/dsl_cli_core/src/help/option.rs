@@ -1,8 +1,8 @@
-use crate::CliOption;
+use crate::{CliOption, help::HelpEntry};
 
 impl CliOption {
-    // Takes in an option and returns something like: (<flags> ...<name>,description)
-    pub fn info(&self) -> (String, String) {
+    // Takes in an option and returns an entry named something like: <flags> ...<name>
+    pub fn help_entry(&self) -> HelpEntry {
         let flags = self.flags.to_string();
 
         let name = self
@@ -12,10 +12,37 @@ impl CliOption {
             .collect::<Vec<String>>()
             .join(" ");
 
-        let usage = [flags, name].join(" ");
+        let usage = if name.is_empty() {
+            flags
+        } else {
+            [flags, name].join(" ")
+        };
 
-        let description = self.description.clone().unwrap_or_default();
+        // Options take the defaults and possible values of their arguments
+        let defaults = self
+            .args
+            .iter()
+            .filter_map(|arg| arg.default.clone())
+            .collect::<Vec<String>>();
+        let choices = self
+            .args
+            .iter()
+            .find(|arg| !arg.choices.is_empty())
+            .map(|arg| arg.choices.clone())
+            .unwrap_or_default();
 
-        (usage, description)
+        HelpEntry {
+            required: !self.optional,
+            default: (!defaults.is_empty()).then(|| defaults.join(" ")),
+            env: self.env.clone(),
+            choices,
+            deprecated: self.deprecated.clone(),
+            ..HelpEntry::new(usage, self.description.clone())
+        }
+    }
+    // Takes in an option and returns something like: (<flags> ...<name>,description)
+    #[deprecated(since = "0.3.0", note = "use `help_entry()` instead")]
+    pub fn info(&self) -> (String, String) {
+        self.help_entry().into_pair()
     }
 }
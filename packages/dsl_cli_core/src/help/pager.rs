@@ -0,0 +1,56 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::{Cli, style::Stream};
+
+// Disables the pager when set to any non-empty value
+pub const NO_PAGER_ENV: &str = "DSL_CLI_NO_PAGER";
+
+const DEFAULT_PAGER: &str = "less -R";
+
+impl Cli {
+    // Prints the text, through the pager when stdout is a terminal and the text doesn't fit on it
+    pub(crate) fn page_or_print(&self, text: &str) {
+        let paged = self.pager && self.is_terminal(Stream::Stdout) && needs_pager(text);
+        if !paged || !page(text) {
+            self.write_to(Stream::Stdout, text);
+        }
+    }
+}
+
+fn needs_pager(text: &str) -> bool {
+    if std::env::var_os(NO_PAGER_ENV).is_some_and(|v| !v.is_empty()) {
+        return false;
+    }
+
+    match terminal_size::terminal_size() {
+        Some((_, terminal_size::Height(height))) => text.lines().count() > height as usize,
+        None => false,
+    }
+}
+
+// Returns false when the pager couldn't be started, so the caller can print instead.
+// `$PAGER` may hold arguments (e.g. `less -FR`), an empty `$PAGER` disables paging.
+fn page(text: &str) -> bool {
+    let pager = std::env::var("PAGER").unwrap_or_else(|_| DEFAULT_PAGER.to_owned());
+    let mut parts = pager.split_whitespace();
+    let Some(program) = parts.next() else {
+        return false;
+    };
+
+    let Ok(mut child) = Command::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .spawn()
+    else {
+        return false;
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user may quit the pager before reading everything, a broken pipe is expected
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    let _ = child.wait();
+
+    true
+}
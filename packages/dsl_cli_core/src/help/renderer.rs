@@ -0,0 +1,180 @@
+use crate::{
+    Theme,
+    help::{
+        HelpDoc, HelpEntry, HelpSectionKind,
+        layout::{HelpLayout, display_width, wrap},
+    },
+    locale::{Message, Messages},
+};
+
+// Turns a help page into text, set a custom one with `Cli::set_help_renderer`
+pub trait HelpRenderer {
+    fn render(&self, doc: &HelpDoc) -> String;
+}
+
+// The default renderer: aligned columns wrapped to `width`, styled with `theme`
+pub struct PlainTextRenderer {
+    width: usize,
+    theme: Theme,
+    messages: Messages,
+}
+
+impl PlainTextRenderer {
+    pub fn new(width: usize, theme: Theme) -> Self {
+        Self {
+            width,
+            theme,
+            messages: Messages::default(),
+        }
+    }
+    // Labels like `Usage:` and `[required]` in another language, see `Cli::messages`
+    pub fn set_messages(&mut self, messages: Messages) -> &mut Self {
+        self.messages = messages;
+        self
+    }
+}
+
+impl HelpRenderer for PlainTextRenderer {
+    fn render(&self, doc: &HelpDoc) -> String {
+        let theme = &self.theme;
+
+        // One layout for every section, so descriptions line up across sections
+        let layout = HelpLayout::new(
+            self.width,
+            doc.sections
+                .iter()
+                .flat_map(|section| section.entries.iter())
+                .map(|entry| entry.name.as_str()),
+        );
+
+        let mut lines = Vec::new();
+
+        if let Some(before_help) = &doc.before_help {
+            lines.push(String::new());
+            lines.extend(layout.render_paragraph(before_help, 0));
+        }
+
+        // Display Usage and Description
+        lines.push(String::new());
+
+        let usage_label = self.messages.get(Message::UsageLabel);
+        let usage_lines = layout.render_paragraph(
+            &format!("{} {}", usage_label, doc.usage),
+            display_width(&usage_label) + 1,
+        );
+        for (idx, line) in usage_lines.iter().enumerate() {
+            match line
+                .strip_prefix(&format!("{} ", usage_label))
+                .filter(|_| idx == 0)
+            {
+                Some(usage) => lines.push(format!(
+                    "{} {}",
+                    theme.header.paint(&usage_label),
+                    theme.paint_entry(usage)
+                )),
+                None => lines.push(theme.paint_entry(line)),
+            }
+        }
+
+        let description = doc
+            .description
+            .clone()
+            .unwrap_or_else(|| self.messages.get(Message::NoDescription));
+        lines.extend(layout.render_paragraph(&description, 0));
+
+        // Display Sections
+        for section in doc.sections.iter().filter(|s| !s.entries.is_empty()) {
+            lines.push(String::new());
+            lines.push(theme.header.paint(&format!("{}:", section.title)));
+
+            for entry in &section.entries {
+                let styled_name = match section.kind {
+                    HelpSectionKind::Commands => paint_command_entry(theme, &entry.name),
+                    _ => theme.paint_entry(&entry.name),
+                };
+                let description = self.describe(entry, section.kind);
+                let description = description.as_str();
+
+                // Style the name once the layout has been computed on the plain text
+                for (idx, line) in layout
+                    .render_row(&entry.name, description)
+                    .into_iter()
+                    .enumerate()
+                {
+                    if idx == 0 {
+                        lines.push(line.replacen(&entry.name, &styled_name, 1));
+                    } else {
+                        lines.push(line);
+                    }
+                }
+            }
+        }
+
+        // Display Examples
+        if !doc.examples.is_empty() {
+            lines.push(String::new());
+            lines.push(
+                theme
+                    .header
+                    .paint(&self.messages.get(Message::ExamplesLabel)),
+            );
+            for example in &doc.examples {
+                lines.extend(
+                    wrap(&example.description, self.width.saturating_sub(2))
+                        .into_iter()
+                        .map(|line| format!("  {}", line)),
+                );
+                lines.push(format!("    $ {}", theme.command.paint(&example.command)));
+            }
+        }
+
+        if let Some(after_help) = &doc.after_help {
+            lines.push(String::new());
+            lines.extend(layout.render_paragraph(after_help, 0));
+        }
+
+        lines.push(String::new());
+        lines.join("\n") + "\n"
+    }
+}
+
+impl PlainTextRenderer {
+    // The description followed by the entry's annotations, e.g. `Port [default: 8080] [env: PORT]`.
+    // Arguments already show whether they're required through `<arg>` / `[arg]`.
+    fn describe(&self, entry: &HelpEntry, kind: HelpSectionKind) -> String {
+        let mut parts = Vec::new();
+
+        if let Some(description) = entry.description.as_deref().filter(|d| !d.is_empty()) {
+            parts.push(description.to_owned());
+        }
+        if entry.required && kind == HelpSectionKind::Options {
+            parts.push(self.messages.get(Message::RequiredAnnotation));
+        }
+        if let Some(default) = &entry.default {
+            parts.push(self.messages.get(Message::DefaultAnnotation(default)));
+        }
+        if let Some(env) = &entry.env {
+            parts.push(self.messages.get(Message::EnvAnnotation(env)));
+        }
+        if !entry.choices.is_empty() {
+            let choices = entry.choices.join(", ");
+            parts.push(
+                self.messages
+                    .get(Message::PossibleValuesAnnotation(&choices)),
+            );
+        }
+        if let Some(note) = &entry.deprecated {
+            parts.push(self.messages.get(Message::DeprecatedAnnotation(note)));
+        }
+
+        parts.join(" ")
+    }
+}
+
+// Styles an entry of the command list like `help [command]`
+fn paint_command_entry(theme: &Theme, entry: &str) -> String {
+    match entry.split_once(' ') {
+        Some((name, rest)) => format!("{} {}", theme.command.paint(name), theme.paint_entry(rest)),
+        None => theme.command.paint(entry),
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{Cli, error::is_similar};
+
+// A command, option or argument matching a `help search` query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    // e.g. `push`, `push --force`, `push <remote>`
+    pub path: String,
+    pub description: String,
+}
+
+impl Cli {
+    // Fuzzy search over command names, descriptions, flags and argument names. Every word of the
+    // query has to appear in (or be a likely typo of a word of) the entry's text.
+    pub fn search_help(&self, query: &str) -> Vec<SearchResult> {
+        let terms = words(query);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+        let matches = |haystack: &[&str]| {
+            let text = haystack.join(" ").to_lowercase();
+            let text_words = words(&text);
+            terms.iter().all(|term| {
+                // Stricter than typo suggestions, descriptions have a lot of words to match
+                let max_distance = (term.chars().count() / 4).max(1);
+                text.contains(term.as_str())
+                    || text_words
+                        .iter()
+                        .any(|word| is_similar(term, word, max_distance))
+            })
+        };
+
+        let mut results = Vec::new();
+        for cmd in self
+            .commands
+            .iter()
+            .filter(|cmd| self.is_listed(cmd.is_hidden()))
+        {
+            let cmd_description = cmd.description.clone().unwrap_or_default();
+            if matches(&[&cmd.name, &cmd_description]) {
+                results.push(SearchResult {
+                    path: cmd.name.clone(),
+                    description: cmd_description,
+                });
+            }
+
+            for arg in cmd
+                .arguments
+                .iter()
+                .filter(|arg| self.is_listed(arg.hidden))
+            {
+                let description = arg.description.clone().unwrap_or_default();
+                if matches(&[&arg.name, &description]) {
+                    results.push(SearchResult {
+                        path: format!("{} {}", cmd.name, arg.reconstruct_name()),
+                        description,
+                    });
+                }
+            }
+
+            for opt in cmd
+                .options
+                .iter()
+                .filter(|opt| self.is_listed(opt.is_hidden()))
+            {
+                let description = opt.description.clone().unwrap_or_default();
+                let flags = opt.flags.values().into_iter().flatten().collect::<Vec<_>>();
+                let mut haystack = vec![opt.name.as_str(), description.as_str()];
+                haystack.extend(flags.iter().map(|flag| flag.as_str()));
+                haystack.extend(opt.args.iter().map(|arg| arg.name.as_str()));
+                if matches(&haystack) {
+                    results.push(SearchResult {
+                        path: format!("{} {}", cmd.name, opt.help_entry().name),
+                        description,
+                    });
+                }
+            }
+        }
+
+        results
+    }
+}
+
+// Lowercase words, flags lose their dashes so `--force` finds `force`
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
+        .map(|word| word.trim_start_matches('-').to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
@@ -0,0 +1,119 @@
+use std::fmt::{self, Write};
+
+// Just enough JSON to describe the CLI for tooling, so the crate doesn't need serde
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Number(usize),
+    // Numbers that can be negative, e.g. exit codes
+    Integer(i64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub(crate) fn object<'a>(fields: impl IntoIterator<Item = (&'a str, Json)>) -> Self {
+        Json::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key.to_owned(), value))
+                .collect(),
+        )
+    }
+    pub(crate) fn string(value: impl Into<String>) -> Self {
+        Json::String(value.into())
+    }
+    pub(crate) fn optional_string(value: Option<impl Into<String>>) -> Self {
+        value.map_or(Json::Null, Json::string)
+    }
+    pub(crate) fn optional_number(value: Option<usize>) -> Self {
+        value.map_or(Json::Null, Json::Number)
+    }
+    // Pretty printed with two spaces of indentation
+    pub(crate) fn to_pretty_string(&self) -> String {
+        let mut out = String::new();
+        self.write_pretty(&mut out, 0).unwrap();
+        out
+    }
+    fn write_pretty(&self, out: &mut String, depth: usize) -> fmt::Result {
+        let indent = "  ".repeat(depth + 1);
+        let closing_indent = "  ".repeat(depth);
+
+        match self {
+            Json::Array(items) if !items.is_empty() => {
+                out.push_str("[\n");
+                for (idx, item) in items.iter().enumerate() {
+                    out.push_str(&indent);
+                    item.write_pretty(out, depth + 1)?;
+                    out.push_str(if idx + 1 < items.len() { ",\n" } else { "\n" });
+                }
+                write!(out, "{}]", closing_indent)
+            }
+            Json::Object(fields) if !fields.is_empty() => {
+                out.push_str("{\n");
+                for (idx, (key, value)) in fields.iter().enumerate() {
+                    write!(out, "{}{}: ", indent, escape(key))?;
+                    value.write_pretty(out, depth + 1)?;
+                    out.push_str(if idx + 1 < fields.len() { ",\n" } else { "\n" });
+                }
+                write!(out, "{}}}", closing_indent)
+            }
+            _ => write!(out, "{}", self),
+        }
+    }
+}
+
+// Compact form
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(value) => write!(f, "{}", value),
+            Json::Integer(value) => write!(f, "{}", value),
+            Json::String(value) => write!(f, "{}", escape(value)),
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (idx, item) in items.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (idx, (key, value)) in fields.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}:{}", escape(key), value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
@@ -1,9 +1,28 @@
 #![allow(unused)]
 
+mod completion;
 mod error;
+pub mod exit_code;
 mod help;
+mod json;
+mod locale;
 mod parse;
+mod style;
+mod terminal;
 mod types;
 
-pub use parse::FromParsed;
-pub use types::{Cli, CliArgument, CliCommand, CliOption, CliOptionFlags};
+pub use completion::Shell;
+pub use error::{ERROR_FORMAT_ENV, ErrorFormat, ErrorReport, ParseError, ParseErrorKind};
+pub use exit_code::{ExitCodePolicy, exit_with};
+pub use help::{
+    DEFINITION_SCHEMA_VERSION, HelpDoc, HelpEntry, HelpExample, HelpOrder, HelpRenderer,
+    HelpSection, HelpSectionKind, NO_PAGER_ENV, PlainTextRenderer, SearchResult,
+};
+pub use locale::{English, HelpTopic, Message, MessageCatalog, Messages, env_locale};
+pub use parse::{FromParsed, ParseExit};
+pub use style::{Color, ColorChoice, Stream, Style, Theme};
+pub use terminal::{CapturedOutput, StdTerminal, Terminal};
+pub use types::{
+    Autocorrect, Cli, CliArgument, CliCommand, CliOption, CliOptionFlags, PlaceholderStyle,
+    ValueHint,
+};
@@ -0,0 +1,131 @@
+use crate::locale::{HelpTopic, Message, MessageCatalog};
+
+// The built-in messages, used for anything a translation doesn't cover
+#[derive(Debug, Clone, Copy, Default)]
+pub struct English;
+
+impl MessageCatalog for English {
+    fn translate(&self, message: &Message) -> Option<String> {
+        Some(english(message))
+    }
+}
+
+pub(crate) fn english(message: &Message) -> String {
+    match *message {
+        Message::ErrorLabel => "error:".to_owned(),
+        Message::TipLabel => "tip:".to_owned(),
+        Message::WarningLabel => "warning:".to_owned(),
+        Message::UsageLabel => "Usage:".to_owned(),
+        Message::ExamplesLabel => "Examples:".to_owned(),
+        Message::SectionTitle(kind) => kind.title().to_owned(),
+        Message::UsageNote => "usage:".to_owned(),
+
+        Message::NoDescription => "No description available".to_owned(),
+        Message::HelpCommandDescription => "Show help for a command".to_owned(),
+        Message::CompletionsCommandDescription => {
+            "Print a completion script for bash, zsh or fish".to_owned()
+        }
+        Message::RequiredAnnotation => "[required]".to_owned(),
+        Message::DefaultAnnotation(default) => format!("[default: {}]", default),
+        Message::EnvAnnotation(env) => format!("[env: {}]", env),
+        Message::PossibleValuesAnnotation(values) => format!("[possible values: {}]", values),
+        Message::DeprecatedAnnotation(note) => format!("[deprecated: {}]", note),
+        Message::SearchResults(query) => format!("Results for {:?}:", query),
+        Message::NoSearchResults(query) => format!("No results for {:?}", query),
+        Message::SearchUsage(executable) => format!("Usage: {} help search <term>", executable),
+
+        Message::InvalidCommand(command) => format!("Invalid command: {}", command),
+        Message::TooManyArguments(args) => {
+            format!(
+                "Arguments `{}` exceeded the maximum number of arguments",
+                args
+            )
+        }
+        Message::TooManyArgumentsForOption(args, flags) => format!(
+            "Arguments `{}` exceeded the number of values taken by option '{}'",
+            args, flags
+        ),
+        Message::MissingRequiredArguments(args) => format!("Missing required arguments: {}", args),
+        Message::MissingRequiredOptions(opts) => format!("Missing required options: {}", opts),
+        Message::MissingRequiredArgumentsForOption(flags, args) => {
+            format!(
+                "Missing required arguments for option '{}': {}",
+                flags, args
+            )
+        }
+        Message::TooFewValues(arg, min, count) => {
+            format!("{} expects at least {} values, got {}", arg, min, count)
+        }
+        Message::TooManyValues(arg, max, count) => {
+            format!("{} expects at most {} values, got {}", arg, max, count)
+        }
+        Message::InvalidChoice(value, arg) => format!("Invalid value {:?} for {}", value, arg),
+        Message::InvalidMapEntry(entry, flags) => format!(
+            "Invalid entry {:?} for option '{}', expected KEY=VALUE of the option's key and value types",
+            entry, flags
+        ),
+        Message::InvalidOptionValue(value, flags) => format!(
+            "Invalid value {:?} for option '{}', it doesn't match the option's type",
+            value, flags
+        ),
+        Message::DuplicateMapKey(key, flags) => format!(
+            "Key {:?} was provided more than once for option '{}'",
+            key, flags
+        ),
+        Message::InvalidOptionFlag(flag) => format!("Invalid option flag: {:?}", flag),
+        Message::InvalidHelpFormat(format) => format!("Unsupported help format {:?}", format),
+        Message::MissingHelpFormat => "Missing value for `help --format`".to_owned(),
+
+        Message::DidYouMean(suggestion) => format!("Did you mean {}?", suggestion),
+        Message::DidYouMeanOneOf(suggestions) => format!("Did you mean one of {}?", suggestions),
+        Message::AvailableCommands(commands) => format!("Available commands: {}", commands),
+        Message::CommandArguments(command, args) => {
+            format!("Arguments for '{}' command are: {}", command, args)
+        }
+        Message::CommandOptions(command, opts) => {
+            format!("Options for '{}' command are: {}", command, opts)
+        }
+        Message::OptionDefinedAs(definition) => format!("Option is defined as: {}", definition),
+        Message::PossibleValues(values) => format!("Possible values are: {}", values),
+        Message::UniqueMapKeys => "Each key can only be set once per invocation".to_owned(),
+        Message::OptionOfOtherCommand(flag, commands, used_command) => format!(
+            "`{}` is an option of {}, not `{}`",
+            flag, commands, used_command
+        ),
+        Message::AvailableFlags(command, flags) => {
+            format!("Available flags for `{}` are: {}", command, flags)
+        }
+        Message::AvailableShortFlags(command, flags) => {
+            format!("Available short flags for `{}` are: {}", command, flags)
+        }
+        Message::NotACommand(token, suggestion) => {
+            format!("`{}` is not a command. {}", token, suggestion)
+        }
+        Message::MoreHelp(topic, help_command) => {
+            let topic = match topic {
+                HelpTopic::Commands => "commands",
+                HelpTopic::Arguments => "arguments",
+                HelpTopic::RequiredArguments => "required arguments",
+                HelpTopic::Options => "options",
+                HelpTopic::OptionArguments => "option arguments",
+            };
+            format!("( For more help on {} run: `{}` )", topic, help_command)
+        }
+
+        Message::AutocorrectRunning(typo, command, delay) => format!(
+            "`{}` is not a command, running `{}` in {:.1} seconds...",
+            typo, command, delay
+        ),
+        Message::AutocorrectPrompt(command) => format!("Did you mean `{}`? [y/N] ", command),
+
+        Message::DeprecatedCommand(command, note) => {
+            format!("The command `{}` is deprecated: {}", command, note)
+        }
+        Message::DeprecatedOption(flag, note) => {
+            format!("The option `{}` is deprecated: {}", flag, note)
+        }
+        Message::DeprecatedArgument(arg, note) => {
+            format!("The argument `{}` is deprecated: {}", arg, note)
+        }
+    }
+}
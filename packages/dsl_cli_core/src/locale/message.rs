@@ -0,0 +1,81 @@
+use crate::help::HelpSectionKind;
+
+// Every built-in string of the help and error output. Catalogs match on it and return the
+// translated text, the fields are the values to put in it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum Message<'a> {
+    // Prefixes and headers
+    ErrorLabel,
+    TipLabel,
+    WarningLabel,
+    UsageLabel,
+    ExamplesLabel,
+    SectionTitle(HelpSectionKind),
+    // Shown under the marked command line of an error, lowercase like rustc's notes
+    UsageNote,
+
+    // Help pages
+    NoDescription,
+    HelpCommandDescription,
+    CompletionsCommandDescription,
+    RequiredAnnotation,
+    DefaultAnnotation(&'a str),        // default value
+    EnvAnnotation(&'a str),            // environment variable
+    PossibleValuesAnnotation(&'a str), // values, comma separated
+    DeprecatedAnnotation(&'a str),     // note
+    SearchResults(&'a str),            // query
+    NoSearchResults(&'a str),          // query
+    SearchUsage(&'a str),              // executable
+
+    // Errors
+    InvalidCommand(&'a str),                             // command
+    TooManyArguments(&'a str),                           // extra arguments
+    TooManyArgumentsForOption(&'a str, &'a str),         // extra arguments, option flags
+    MissingRequiredArguments(&'a str),                   // arguments
+    MissingRequiredOptions(&'a str),                     // options
+    MissingRequiredArgumentsForOption(&'a str, &'a str), // option flags, arguments
+    TooFewValues(&'a str, usize, usize), // argument, minimum, number of values provided
+    TooManyValues(&'a str, usize, usize), // argument, maximum, number of values provided
+    InvalidChoice(&'a str, &'a str),     // value, argument
+    InvalidMapEntry(&'a str, &'a str),   // entry, option flags
+    InvalidOptionValue(&'a str, &'a str), // value, option flags
+    DuplicateMapKey(&'a str, &'a str),   // key, option flags
+    InvalidOptionFlag(&'a str),          // flag
+    InvalidHelpFormat(&'a str),          // value of `help --format`
+    MissingHelpFormat,
+
+    // Tips
+    DidYouMean(&'a str),                // suggestion
+    DidYouMeanOneOf(&'a str),           // suggestions, comma separated
+    AvailableCommands(&'a str),         // commands, comma separated
+    CommandArguments(&'a str, &'a str), // command, arguments
+    CommandOptions(&'a str, &'a str),   // command, options
+    OptionDefinedAs(&'a str),           // option with its arguments
+    PossibleValues(&'a str),            // values, comma separated
+    UniqueMapKeys,
+    OptionOfOtherCommand(&'a str, &'a str, &'a str), // flag, commands, used command
+    AvailableFlags(&'a str, &'a str),                // command, flags
+    AvailableShortFlags(&'a str, &'a str),           // command, flags
+    NotACommand(&'a str, &'a str),                   // token, suggestion
+    MoreHelp(HelpTopic, &'a str),                    // topic, help command to run
+
+    // Autocorrect
+    AutocorrectRunning(&'a str, &'a str, f64), // typo, command, delay in seconds
+    AutocorrectPrompt(&'a str),                // command
+
+    // Deprecation warnings
+    DeprecatedCommand(&'a str, &'a str),  // command, note
+    DeprecatedOption(&'a str, &'a str),   // flag as written, note
+    DeprecatedArgument(&'a str, &'a str), // argument, note
+}
+
+// What the `( For more help on ... )` line of an error is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HelpTopic {
+    Commands,
+    Arguments,
+    RequiredArguments,
+    Options,
+    OptionArguments,
+}
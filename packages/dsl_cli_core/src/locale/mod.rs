@@ -0,0 +1,52 @@
+mod english;
+mod message;
+
+use std::rc::Rc;
+
+pub use english::English;
+pub(crate) use english::english;
+pub use message::{HelpTopic, Message};
+
+// Translations of the built-in messages, see `Cli::add_translation`
+pub trait MessageCatalog {
+    // `None` falls back to the English message
+    fn translate(&self, message: &Message) -> Option<String>;
+}
+
+// The catalog picked for the current locale, English when there is none
+#[derive(Clone, Default)]
+pub struct Messages {
+    catalog: Option<Rc<dyn MessageCatalog>>,
+}
+
+impl Messages {
+    pub fn new(catalog: Option<Rc<dyn MessageCatalog>>) -> Self {
+        Self { catalog }
+    }
+    pub fn get(&self, message: Message) -> String {
+        self.catalog
+            .as_ref()
+            .and_then(|catalog| catalog.translate(&message))
+            .unwrap_or_else(|| english(&message))
+    }
+}
+
+// The locale asked for by the environment, e.g. `de_DE` for `LANG=de_DE.UTF-8`. `LC_ALL` takes
+// priority over `LC_MESSAGES`, which takes priority over `LANG`.
+pub fn env_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .map(|value| normalize_locale(&value))
+        .filter(|locale| !matches!(locale.as_str(), "C" | "POSIX"))
+}
+
+// `de-DE.UTF-8@euro` -> `de_DE`
+pub(crate) fn normalize_locale(locale: &str) -> String {
+    locale
+        .split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .replace('-', "_")
+}
@@ -0,0 +1,155 @@
+use std::io::{BufRead, IsTerminal};
+
+use crate::{Cli, error::similar_candidates, locale::Message, style::Stream, types::Autocorrect};
+
+impl Cli {
+    // The command to run instead of a mistyped one, when autocorrect is on and there is a single
+    // close match (and the user agreed to it in prompt mode)
+    pub(crate) fn autocorrect_command(&mut self, typo: &str) -> Option<String> {
+        let mode = std::env::var(self.autocorrect_env())
+            .ok()
+            .and_then(|value| Autocorrect::from_env_value(&value))
+            .unwrap_or(self.autocorrect);
+        if mode == Autocorrect::Off || typo.is_empty() || typo.starts_with('-') {
+            return None;
+        }
+
+        let mut similar = similar_candidates(typo.to_owned(), self.command_names());
+        if similar.len() != 1 {
+            return None;
+        }
+        let command = similar.remove(0);
+
+        match mode {
+            Autocorrect::Off => None,
+            Autocorrect::Prompt => self.confirm(&command).then_some(command),
+            Autocorrect::Run => {
+                self.warn(&self.message(Message::AutocorrectRunning(
+                    typo,
+                    &command,
+                    self.autocorrect_delay.as_secs_f64(),
+                )));
+                std::thread::sleep(self.autocorrect_delay);
+                Some(command)
+            }
+        }
+    }
+
+    // e.g. `MY_APP_AUTOCORRECT` for an app named `my-app`
+    pub(crate) fn autocorrect_env(&self) -> String {
+        let app = self
+            .name
+            .chars()
+            .map(|c| match c.is_ascii_alphanumeric() {
+                true => c.to_ascii_uppercase(),
+                false => '_',
+            })
+            .collect::<String>();
+        format!("{}_AUTOCORRECT", app)
+    }
+
+    // Asks on stderr and reads the answer, only a `y` / `yes` counts as a yes
+    fn confirm(&mut self, command: &str) -> bool {
+        // Nobody can answer when stdin isn't a terminal, so don't wait on it
+        if self.autocorrect_input.is_none() && !std::io::stdin().is_terminal() {
+            return false;
+        }
+
+        self.write_to(
+            Stream::Stderr,
+            &self.message(Message::AutocorrectPrompt(command)),
+        );
+
+        let mut answer = String::new();
+        let read = match &mut self.autocorrect_input {
+            Some(input) => input.read_line(&mut answer),
+            None => std::io::stdin().lock().read_line(&mut answer),
+        };
+
+        read.is_ok() && matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Cursor, IsTerminal},
+        time::Duration,
+    };
+
+    use crate::{Autocorrect, CapturedOutput, Cli, CliArgument};
+
+    // A `push <remote>` command next to a `cli` fallback taking an optional `[file]`, so a typo
+    // of `push` is also a valid `cli` invocation. Returns what is written to stderr.
+    fn test_cli(autocorrect: Autocorrect) -> (Cli, CapturedOutput) {
+        let mut cli = Cli::new("autocorrect-test", "1.0.0", "Autocorrect test");
+        cli.add_command("push", Some("Push commits"))
+            .add_argument(CliArgument::new(
+                "remote".to_owned(),
+                None::<String>,
+                false,
+                false,
+            ));
+        cli.add_command("cli", None::<String>)
+            .add_argument(CliArgument::new(
+                "file".to_owned(),
+                None::<String>,
+                true,
+                false,
+            ));
+        let stderr = CapturedOutput::new();
+        cli.set_output(CapturedOutput::new(), stderr.clone())
+            .set_autocorrect(autocorrect)
+            .set_autocorrect_delay(Duration::ZERO);
+        (cli, stderr)
+    }
+
+    fn args(tokens: &[&str]) -> Vec<String> {
+        tokens.iter().map(|token| token.to_string()).collect()
+    }
+
+    #[test]
+    fn prompt_accepted_runs_the_close_command() {
+        let (mut cli, stderr) = test_cli(Autocorrect::Prompt);
+        cli.set_autocorrect_input(Cursor::new("y\n"));
+
+        assert!(cli.try_parse(args(&["psh", "origin"])).is_ok());
+        assert_eq!(cli.used_command(), Some("push"));
+        assert_eq!(stderr.contents(), "Did you mean `push`? [y/N] ");
+    }
+
+    #[test]
+    fn prompt_declined_falls_back_to_cli() {
+        let (mut cli, stderr) = test_cli(Autocorrect::Prompt);
+        cli.set_autocorrect_input(Cursor::new("n\n"));
+
+        assert!(cli.try_parse(args(&["psh"])).is_ok());
+        assert_eq!(cli.used_command(), Some("cli"));
+        assert_eq!(stderr.contents(), "Did you mean `push`? [y/N] ");
+    }
+
+    #[test]
+    fn run_mode_runs_the_close_command_without_asking() {
+        let (mut cli, stderr) = test_cli(Autocorrect::Run);
+
+        assert!(cli.try_parse(args(&["psh", "origin"])).is_ok());
+        assert_eq!(cli.used_command(), Some("push"));
+        assert_eq!(
+            stderr.contents(),
+            "warning: `psh` is not a command, running `push` in 0.0 seconds...\n"
+        );
+    }
+
+    #[test]
+    fn prompt_is_declined_when_stdin_is_not_a_terminal() {
+        // Only meaningful when the tests run with a redirected stdin, as in CI
+        if std::io::stdin().is_terminal() {
+            return;
+        }
+        let (mut cli, stderr) = test_cli(Autocorrect::Prompt);
+
+        assert!(cli.try_parse(args(&["psh"])).is_ok());
+        assert_eq!(cli.used_command(), Some("cli"));
+        assert_eq!(stderr.contents(), "");
+    }
+}
@@ -1,24 +1,146 @@
 use std::{any::Any, collections::HashMap, iter::Peekable};
 
-use crate::{Cli, CliArgument, CliOption, FromParsed, error::ParseError};
+use crate::{
+    Cli, CliArgument, CliCommand, CliOption, FromParsed,
+    completion::Shell,
+    error::ParseError,
+    exit_code::EX_OK,
+    locale::Message,
+    style::{ColorChoice, Stream},
+};
 
 // The Box<dyn Any> represents either None or a String
 type ParsedArgs = HashMap<String, Box<dyn Any>>;
 type ParsedOpts = HashMap<String, Box<dyn Any>>;
+// Tokens paired with their index in the command line, for error positions
+type Tokens = Peekable<std::vec::IntoIter<(usize, String)>>;
+
+// Why parsing stopped before producing values. Whatever there was to show (help, a completion
+// script, the error) has already been written to the Cli's output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseExit {
+    // Help, the JSON definition or a completion script was printed
+    Help,
+    // The command line was invalid, with the exit code for it
+    Error(i32),
+}
+
+impl ParseExit {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ParseExit::Help => EX_OK,
+            ParseExit::Error(code) => *code,
+        }
+    }
+}
+
+// Why `parse_tokens` stopped early, errors are only written out by `try_parse`
+enum Stop {
+    Exit(ParseExit),
+    Error(ParseError),
+}
+
+impl From<ParseError> for Stop {
+    fn from(e: ParseError) -> Self {
+        Stop::Error(e)
+    }
+}
 
 impl Cli {
+    // Parses the command line, exiting the process on help and errors
     pub fn parse(&mut self, env_args: Vec<String>) -> (ParsedArgs, ParsedOpts) {
-        let result = self.try_parse(env_args);
-
-        match result {
-            Ok((parsed_args, parsed_opts)) => (parsed_args, parsed_opts),
-            Err(e) => {
+        self.try_parse(env_args)
+            .unwrap_or_else(|exit| std::process::exit(exit.exit_code()))
+    }
+    // Same as `parse`, but returns instead of exiting, e.g. for tests or to clean up first
+    pub fn try_parse(
+        &mut self,
+        env_args: Vec<String>,
+    ) -> Result<(ParsedArgs, ParsedOpts), ParseExit> {
+        let env_args = self.take_color_flag(env_args);
+        // Nothing from a previous parse carries over, `help --hidden` only lasts for this one
+        self.invocation = env_args.clone();
+        self.used_command = None;
+        self.unmatched_command = None;
+        self.env_values.clear();
+        self.builtin_usage = None;
+        let show_hidden = self.show_hidden;
+
+        let result = match self.parse_tokens(env_args) {
+            Ok((parsed_args, parsed_opts)) => Ok((parsed_args, parsed_opts)),
+            Err(Stop::Exit(exit)) => Err(exit),
+            Err(Stop::Error(e)) => {
+                let exit_code = self.exit_code(&e);
                 self.handle_parse_error(e);
-                std::process::exit(1);
+                Err(ParseExit::Error(exit_code))
             }
+        };
+        self.show_hidden = show_hidden;
+        result
+    }
+    // Consumes `--color=always|never|auto` anywhere on the command line and applies it, unless
+    // one of the commands defines its own `--color` option
+    fn take_color_flag(&mut self, env_args: Vec<String>) -> Vec<String> {
+        let user_defined = self.commands.iter().any(|cmd| {
+            cmd.options
+                .iter()
+                .any(|opt| opt.flags.values()[1].as_deref() == Some("color"))
+        });
+        if user_defined {
+            return env_args;
+        }
+
+        env_args
+            .into_iter()
+            .filter(|token| {
+                let choice = token
+                    .strip_prefix("--color=")
+                    .and_then(ColorChoice::from_flag_value);
+                if let Some(choice) = choice {
+                    self.color = choice;
+                }
+                choice.is_none()
+            })
+            .collect()
+    }
+
+    // `-h` shows the short help, `--help` the long one
+    fn show_help_for_flag(&self, command_name: &str, flag: &str) -> Stop {
+        if flag == "-h" {
+            self.show_short_help(command_name.to_owned());
+        } else {
+            self.show_help(command_name.to_owned());
         }
+        Stop::Exit(ParseExit::Help)
     }
-    fn try_parse(&mut self, env_args: Vec<String>) -> Result<(ParsedArgs, ParsedOpts), ParseError> {
+
+    // `-h` / `--help` anywhere in flag position, unless the command defines them itself. Right
+    // after an option that requires a value they are that option's missing value, not a request
+    // for help, e.g. `push -o -h`
+    fn help_flag<'a>(command_def: &CliCommand, env_args: &'a [String]) -> Option<&'a str> {
+        let mut expects_value = false;
+        for token in env_args {
+            let opt_def = command_def
+                .options
+                .iter()
+                .find(|opt| opt.flags == *token)
+                .map(|opt| {
+                    // Old spellings take the values of the option that replaced them
+                    opt.replaced_by
+                        .as_ref()
+                        .and_then(|name| command_def.options.iter().find(|o| &o.name == name))
+                        .unwrap_or(opt)
+                });
+            if opt_def.is_none() && !expects_value && matches!(token.as_str(), "-h" | "--help") {
+                return Some(token);
+            }
+            expects_value =
+                opt_def.is_some_and(|opt| opt.args.first().is_some_and(|arg| !arg.optional));
+        }
+        None
+    }
+
+    fn parse_tokens(&mut self, env_args: Vec<String>) -> Result<(ParsedArgs, ParsedOpts), Stop> {
         let potential_cmd_name = &env_args
             .first()
             .map(|s| s.to_owned())
@@ -27,18 +149,90 @@ impl Cli {
         let potential_cmd_name = potential_cmd_name.as_str();
 
         if potential_cmd_name == "help" {
+            // `help --hidden ...` lists hidden commands, options and arguments as well
+            let mut env_args = env_args;
+            let mut command_idx = 1;
+            if env_args.get(1).is_some_and(|token| token == "--hidden") {
+                env_args.remove(1);
+                self.show_hidden = true;
+                command_idx = 2;
+            }
             let second = env_args.get(1).map(|s| s.to_owned());
 
+            // Hidden flag for tooling: dumps the whole definition instead of the help text.
+            // The token index points at the value, or one past `--format` when it is missing
+            let format = match second.as_deref() {
+                Some("--format") => Some((env_args.get(2).cloned(), command_idx + 1)),
+                Some(flag) => flag
+                    .strip_prefix("--format=")
+                    .map(|value| (Some(value.to_owned()), command_idx)),
+                None => None,
+            };
+            if let Some((format, token_idx)) = format {
+                if format.as_deref() != Some("json") {
+                    return Err(ParseError::InvalidHelpFormat(token_idx, format).into());
+                }
+                self.write_to(Stream::Stdout, &(self.definition_json() + "\n"));
+                return Err(Stop::Exit(ParseExit::Help));
+            }
+
+            if second.as_deref() == Some("--all") {
+                self.show_all_help();
+                return Err(Stop::Exit(ParseExit::Help));
+            }
+
+            // `help search` is the help of a command named `search` when one exists
+            let search_command_exists = self.commands.iter().any(|cmd| cmd.name == "search");
+            if second.as_deref() == Some("search") && (env_args.len() > 2 || !search_command_exists)
+            {
+                let query = env_args[2..].join(" ");
+                if query.trim().is_empty() {
+                    let usage = self.message(Message::SearchUsage(&self.executable_name));
+                    self.write_to(Stream::Stdout, &(usage + "\n"));
+                } else {
+                    self.show_search_results(&query);
+                }
+                return Err(Stop::Exit(ParseExit::Help));
+            }
+
             if let Some(second) = second {
-                if let None = self.commands.iter().find(|cmd| cmd.name == second) {
-                    return Err(ParseError::InvalidCommand(second.to_string()));
+                if !self.commands.iter().any(|cmd| cmd.name == second) {
+                    return Err(ParseError::InvalidCommand(command_idx, second.to_string()).into());
                 }
 
-                self.show_help(second.to_string());
+                self.show_help(self.replacement_command(&second));
             } else {
                 self.show_help("cli".to_owned());
             }
-            std::process::exit(0);
+            return Err(Stop::Exit(ParseExit::Help));
+        }
+
+        if potential_cmd_name == "completions" && self.has_completions_command() {
+            let shell_names = Shell::ALL
+                .iter()
+                .map(|shell| shell.name().to_owned())
+                .collect::<Vec<String>>();
+            self.builtin_usage = Some(format!("{} completions <shell>", self.executable_name));
+            let Some(value) = env_args.get(1) else {
+                return Err(
+                    ParseError::MissingRequiredArguments(1, vec!["<shell>".to_owned()]).into(),
+                );
+            };
+            let Some(shell) = Shell::from_name(value) else {
+                return Err(ParseError::InvalidChoice(
+                    1,
+                    "<shell>".to_owned(),
+                    value.to_owned(),
+                    shell_names,
+                )
+                .into());
+            };
+
+            let mut script = Vec::new();
+            // Writing to a Vec can't fail
+            let _ = self.generate_completion(shell, &mut script);
+            self.write_to(Stream::Stdout, &String::from_utf8_lossy(&script));
+            return Err(Stop::Exit(ParseExit::Help));
         }
 
         let possible_command_names = self
@@ -46,28 +240,49 @@ impl Cli {
             .iter()
             .map(|cmd| cmd.name.as_str())
             .collect::<Vec<&str>>();
+        let has_cli_command = possible_command_names.contains(&"cli");
 
         let mut env_args = env_args.into_iter();
+        // Index of the first token after the command name
+        let mut offset = 0;
         let command_def = if possible_command_names.contains(&potential_cmd_name) {
-            self.used_command = Some(potential_cmd_name.to_owned());
+            let command_name = self.resolve_command(potential_cmd_name);
+            self.used_command = Some(command_name.clone());
             env_args.next();
+            offset = 1;
             self.commands
                 .iter()
-                .find(|cmd| cmd.name == potential_cmd_name)
+                .find(|cmd| cmd.name == command_name)
                 .unwrap()
-        } else if possible_command_names.contains(&"cli") {
+        } else if let Some(command) = self.autocorrect_command(potential_cmd_name) {
+            // Before the `cli` fallback, which would otherwise take the typo as its own token
+            let mut env_args = env_args.collect::<Vec<String>>();
+            env_args[0] = command;
+            return self.parse_tokens(env_args);
+        } else if has_cli_command {
             self.used_command = Some("cli".to_owned());
-            &self.commands.iter().find(|cmd| cmd.name == "cli").unwrap()
+            self.unmatched_command = Some(potential_cmd_name.to_owned());
+            self.commands.iter().find(|cmd| cmd.name == "cli").unwrap()
+        } else if matches!(potential_cmd_name, "-h" | "--help") {
+            return Err(self.show_help_for_flag("cli", potential_cmd_name));
         } else {
-            return Err(ParseError::InvalidCommand(potential_cmd_name.to_string()));
+            return Err(ParseError::InvalidCommand(0, potential_cmd_name.to_string()).into());
         };
         let env_args = env_args.collect::<Vec<String>>();
 
+        if let Some(flag) = Self::help_flag(command_def, &env_args) {
+            return Err(self.show_help_for_flag(&command_def.name, flag));
+        }
+
+        self.warn_deprecated_options(command_def, &env_args);
         let (parsed_args, parsed_opts) = Self::parse_args(
             env_args,
             command_def.arguments.clone(),
             command_def.options.clone(),
+            offset,
+            &mut self.env_values,
         )?;
+        self.warn_deprecated_arguments(command_def, &parsed_args);
 
         Ok((parsed_args, parsed_opts))
     }
@@ -76,24 +291,51 @@ impl Cli {
         env_args: Vec<String>,
         template_args: Vec<CliArgument>,
         template_opts: Vec<CliOption>,
+        offset: usize,
+        env_values: &mut Vec<(String, String)>,
     ) -> Result<(ParsedArgs, ParsedOpts), ParseError> {
+        // Errors about missing values point right after the last token
+        let end = offset + env_args.len();
         let mut parsed_args = Self::initialize_parsed_args(&template_args);
         let mut parsed_opts = Self::initialize_parsed_opts(&template_opts);
-        let mut tokens = env_args.into_iter().peekable();
-        let mut positional_idx = 0;
+        let mut tokens = env_args
+            .into_iter()
+            .enumerate()
+            .map(|(idx, token)| (offset + idx, token))
+            .collect::<Vec<(usize, String)>>()
+            .into_iter()
+            .peekable();
+        let mut positional_tokens = Vec::new();
+        // Set right after a fixed-arity option. Tokens after its values are positionals, they are
+        // only blamed on the option when no positional argument is left to take them.
+        let mut last_fixed_arity_opt = None;
+        let mut fixed_arity_overflows = HashMap::new();
+        // Last occurrence of each option, delimited values are only counted at the end
+        let mut opt_tokens = HashMap::new();
+
+        while let Some((token_idx, token)) = tokens.next() {
+            let previous_fixed_arity_opt = last_fixed_arity_opt.take();
 
-        while let Some(token) = tokens.next() {
             if Self::is_option_token(&token) {
                 // Check if the option is included in the template
                 if !template_opts.iter().any(|opt| opt.flags == token) {
-                    return Err(ParseError::InvalidOptionFlag(token));
+                    return Err(ParseError::InvalidOptionFlag(token_idx, token));
                 }
 
-                let opt_idx = template_opts
+                let mut opt_idx = template_opts
                     .iter()
                     .position(|opt| opt.flags == token)
                     .unwrap();
+                // Old spellings parse as the option that replaced them
+                if let Some(replaced_by) = &template_opts[opt_idx].replaced_by
+                    && let Some(idx) = template_opts
+                        .iter()
+                        .position(|opt| &opt.name == replaced_by)
+                {
+                    opt_idx = idx;
+                }
                 let opt_def = &template_opts[opt_idx];
+                opt_tokens.insert(opt_def.name.clone(), token_idx);
 
                 // Option has no arguments = flag-only option
                 if opt_def.args.is_empty() {
@@ -101,74 +343,412 @@ impl Cli {
                     continue;
                 }
 
-                // Handle positional arguments for option
-                let opt_args = opt_def.args.clone();
-                let mut parsed_opt_args = Self::initialize_parsed_args(&opt_args);
-                let mut idx = 0;
+                Self::parse_option_values(
+                    opt_def,
+                    opt_idx,
+                    token_idx,
+                    &mut tokens,
+                    &mut parsed_opts,
+                    false,
+                )?;
+
+                if opt_def.args.last().is_some_and(|arg| arg.arity.is_some()) {
+                    last_fixed_arity_opt = Some(opt_idx);
+                }
+            } else {
+                // Positional arguments are assigned once every token has been seen, so values
+                // can be reserved for the positionals that come after a variadic
+                if let Some(opt_idx) = previous_fixed_arity_opt {
+                    fixed_arity_overflows.insert(positional_tokens.len(), opt_idx);
+                }
+                positional_tokens.push((token_idx, token));
+            }
+        }
+
+        Self::read_env_options(
+            &template_opts,
+            &opt_tokens,
+            &mut parsed_opts,
+            env_values,
+            end + 1,
+        )?;
+        Self::assign_positional_args(
+            positional_tokens,
+            &template_args,
+            &mut parsed_args,
+            &fixed_arity_overflows,
+            end,
+        )?;
+        Self::check_for_missing_required_opts(&parsed_opts, &template_opts, end)?;
+        Self::check_delimited_value_counts(&parsed_opts, &template_opts, &opt_tokens)?;
 
-                while idx < parsed_opt_args.len() {
-                    if tokens.peek().is_none() || Self::is_option_token(tokens.peek().unwrap()) {
-                        break;
-                    }
+        Ok((parsed_args, parsed_opts))
+    }
 
-                    let arg_def = &opt_args[idx];
-                    let token = tokens.next().unwrap();
-                    let parsed_value = Self::parse_arg(arg_def, token, &mut tokens)?;
+    // ------------------------------------------------------------
+    // Utils
+    // ------------------------------------------------------------
+    // Parses the values following an option flag at `opt_token`. Values read from an environment
+    // variable are taken as they are, even when they start with `-`
+    fn parse_option_values(
+        opt_def: &CliOption,
+        opt_idx: usize,
+        opt_token: usize,
+        tokens: &mut Tokens,
+        parsed_opts: &mut ParsedOpts,
+        from_env: bool,
+    ) -> Result<(), ParseError> {
+        let opt_args = opt_def.args.clone();
+        let mut parsed_opt_args = Self::initialize_parsed_args(&opt_args);
+        let mut idx = 0;
 
-                    // If the option only has one argument, insert the value into the option directly
-                    if parsed_opt_args.len() == 1 {
-                        parsed_opts.insert(opt_def.name.clone(), parsed_value);
-                    } else {
-                        parsed_opt_args.insert(arg_def.name.clone(), parsed_value);
-                    }
+        while idx < parsed_opt_args.len() {
+            if !Self::next_is_value(tokens, from_env) {
+                break;
+            }
 
-                    idx += 1;
-                }
+            let arg_def = &opt_args[idx];
+            let (value_idx, token) = tokens.next().unwrap();
 
-                Self::check_for_missing_required_args(&opt_args, idx, Some(opt_idx))?;
+            // Map options accumulate their entries across repeated occurrences
+            if arg_def.map {
+                Self::insert_map_entry(parsed_opts, opt_def, opt_idx, value_idx, token)?;
+                idx += 1;
+                continue;
+            }
 
-                if opt_def.args.len() > 1 {
-                    parsed_opts.insert(opt_def.name.clone(), Box::new(parsed_opt_args));
+            let parsed_value = Self::parse_arg(
+                arg_def,
+                (value_idx, token),
+                tokens,
+                opt_idx,
+                opt_token,
+                from_env,
+            )?;
+
+            // If the option only has one argument, insert the value into the option directly
+            if parsed_opt_args.len() == 1 {
+                if arg_def.delimiter.is_some() {
+                    Self::extend_delimited_values(parsed_opts, &opt_def.name, parsed_value);
+                } else {
+                    parsed_opts.insert(opt_def.name.clone(), parsed_value);
                 }
             } else {
-                // Check if we've gone past the number of positional arguments
-                if positional_idx >= template_args.len() {
-                    let mut remaining_args = vec![token];
-                    remaining_args.extend(tokens);
-                    return Err(ParseError::TooManyArguments(remaining_args));
+                parsed_opt_args.insert(arg_def.name.clone(), parsed_value);
+            }
+
+            idx += 1;
+        }
+
+        Self::check_for_missing_required_args(&opt_args, idx, opt_idx, opt_token)?;
+
+        if opt_def.args.len() > 1 {
+            parsed_opts.insert(opt_def.name.clone(), Box::new(parsed_opt_args));
+        }
+        Ok(())
+    }
+    // Options missing from the command line are read from their environment variable. Each
+    // variable counts as one token numbered after the command line, starting at `first_token`,
+    // and is recorded in `env_values` so errors can show it. Flags are set by any value except
+    // `0` and `false`.
+    fn read_env_options(
+        template_opts: &[CliOption],
+        opt_tokens: &HashMap<String, usize>,
+        parsed_opts: &mut ParsedOpts,
+        env_values: &mut Vec<(String, String)>,
+        first_token: usize,
+    ) -> Result<(), ParseError> {
+        for (opt_idx, opt_def) in template_opts.iter().enumerate() {
+            // Given on the command line, possibly through an old spelling
+            if opt_tokens.contains_key(&opt_def.name) {
+                continue;
+            }
+            let Some(env) = &opt_def.env else {
+                continue;
+            };
+            let Ok(value) = std::env::var(env) else {
+                continue;
+            };
+
+            if opt_def.args.is_empty() {
+                if !value.is_empty() && value != "0" && !value.eq_ignore_ascii_case("false") {
+                    parsed_opts.insert(opt_def.name.clone(), Box::new(true));
                 }
+                continue;
+            }
 
-                // Handle positional arguments
-                let arg_def = &template_args[positional_idx];
-                let parsed_value = Self::parse_arg(arg_def, token, &mut tokens)?;
-                parsed_args.insert(arg_def.name.clone(), parsed_value);
-                positional_idx += 1;
+            let token_idx = first_token + env_values.len();
+            env_values.push((env.clone(), value.clone()));
+
+            // Values are only split when the option takes several tokens, so paths keep their spaces
+            let takes_many = opt_def.args.len() > 1
+                || opt_def.args.iter().any(|arg| {
+                    (arg.variadic || arg.arity.is_some()) && arg.delimiter.is_none() && !arg.map
+                });
+            let values = match takes_many {
+                true => value.split_whitespace().map(|v| v.to_owned()).collect(),
+                false => vec![value],
+            };
+            let mut tokens = values
+                .into_iter()
+                .map(|value| (token_idx, value))
+                .collect::<Vec<(usize, String)>>()
+                .into_iter()
+                .peekable();
+
+            Self::parse_option_values(opt_def, opt_idx, token_idx, &mut tokens, parsed_opts, true)?;
+
+            let remaining_values = tokens.map(|(_, value)| value).collect::<Vec<String>>();
+            if !remaining_values.is_empty() {
+                return Err(ParseError::TooManyArgumentsForOption(
+                    token_idx,
+                    opt_idx,
+                    remaining_values,
+                ));
             }
         }
+        Ok(())
+    }
+    // `opt_token` is the index of the option flag, `current_token` the first value after it
+    fn parse_arg(
+        arg_def: &CliArgument,
+        current_token: (usize, String),
+        tokens: &mut Tokens,
+        opt_idx: usize,
+        opt_token: usize,
+        from_env: bool,
+    ) -> Result<Box<dyn Any>, ParseError> {
+        let (mut token_indexes, mut values): (Vec<usize>, Vec<String>) =
+            std::iter::once(current_token).unzip();
+
+        if let Some(arity) = arg_def.arity {
+            while values.len() < arity && Self::next_is_value(tokens, from_env) {
+                let (token_idx, token) = tokens.next().unwrap();
+                token_indexes.push(token_idx);
+                values.push(token);
+            }
 
-        Self::check_for_missing_required_args(&template_args, positional_idx, None)?;
-        Self::check_for_missing_required_opts(&parsed_opts, &template_opts)?;
+            if values.len() < arity {
+                let missing_values = (values.len()..arity)
+                    .map(|position| format!("<{}>", arg_def.placeholder(position)))
+                    .collect::<Vec<String>>();
+                return Err(ParseError::MissingRequiredArgumentsForOption(
+                    opt_token,
+                    opt_idx,
+                    missing_values,
+                ));
+            }
 
-        Ok((parsed_args, parsed_opts))
+            if let Some(check) = arg_def.element_check
+                && let Some(position) =
+                    (0..arity).find(|&position| !check(position, &values[position]))
+            {
+                return Err(ParseError::InvalidOptionValue(
+                    token_indexes[position],
+                    opt_idx,
+                    values[position].clone(),
+                ));
+            }
+        } else if arg_def.variadic
+            && let Some(delimiter) = arg_def.delimiter
+        {
+            values = Self::split_delimited(&values[0], delimiter);
+        } else if arg_def.variadic {
+            while Self::next_is_value(tokens, from_env) {
+                let (token_idx, token) = tokens.next().unwrap();
+                token_indexes.push(token_idx);
+                values.push(token);
+            }
+            Self::check_value_count(arg_def, values.len(), opt_token)?;
+        }
+        Self::check_choices(arg_def, &values, &token_indexes)?;
+
+        match arg_def.variadic || arg_def.arity.is_some() {
+            true => Ok(Box::new(values)),
+            false => Ok(Box::new(values.remove(0))),
+        }
     }
 
-    // ------------------------------------------------------------
-    // Utils
-    // ------------------------------------------------------------
-    fn parse_arg(
+    // Distributes the positional tokens over the positional arguments. Required arguments get
+    // their values first, the remaining tokens fill the optional arguments from left to right and
+    // whatever is left goes to the variadic argument, wherever it is placed (e.g. `<src...> <dst>`)
+    fn assign_positional_args(
+        positional_tokens: Vec<(usize, String)>,
+        template_args: &[CliArgument],
+        parsed_args: &mut ParsedArgs,
+        fixed_arity_overflows: &HashMap<usize, usize>,
+        end: usize,
+    ) -> Result<(), ParseError> {
+        let total = positional_tokens.len();
+        let required = template_args
+            .iter()
+            .map(Self::min_positional_tokens)
+            .sum::<usize>();
+
+        // Not enough tokens, report the arguments that are left without values
+        if total < required {
+            let mut available = total;
+            let mut missing_args = Vec::new();
+            for arg_def in template_args {
+                let needed = Self::min_positional_tokens(arg_def);
+                if needed == 0 {
+                    continue;
+                }
+                if available == 0 {
+                    missing_args.push(arg_def.reconstruct_name());
+                } else if available < needed {
+                    if arg_def.variadic && arg_def.delimiter.is_none() {
+                        return Err(ParseError::TooFewValues(
+                            end,
+                            arg_def.reconstruct_name(),
+                            needed,
+                            available,
+                        ));
+                    }
+                    missing_args.push(arg_def.reconstruct_name());
+                }
+                available = available.saturating_sub(needed);
+            }
+            return Err(ParseError::MissingRequiredArguments(end, missing_args));
+        }
+
+        // Fill the optional arguments, then hand the rest to the variadic argument
+        let mut counts = template_args
+            .iter()
+            .map(Self::min_positional_tokens)
+            .collect::<Vec<usize>>();
+        let mut surplus = total - required;
+        for (idx, arg_def) in template_args.iter().enumerate() {
+            let size = arg_def.arity.unwrap_or(1);
+            if arg_def.optional && !Self::takes_many_tokens(arg_def) && surplus >= size {
+                counts[idx] = size;
+                surplus -= size;
+            }
+        }
+        if let Some(idx) = template_args.iter().position(Self::takes_many_tokens) {
+            counts[idx] += surplus;
+            surplus = 0;
+        }
+
+        let mut tokens = positional_tokens.into_iter();
+        for (arg_def, count) in template_args.iter().zip(counts) {
+            if count == 0 {
+                continue;
+            }
+            let values = tokens
+                .by_ref()
+                .take(count)
+                .collect::<Vec<(usize, String)>>();
+            let parsed_value = Self::parse_positional_values(arg_def, values)?;
+            parsed_args.insert(arg_def.name.clone(), parsed_value);
+        }
+
+        if surplus > 0 {
+            let first_extra = total - surplus;
+            let (token_indexes, remaining_args): (Vec<usize>, Vec<String>) = tokens.unzip();
+            let token_idx = token_indexes[0];
+            if let Some(opt_idx) = fixed_arity_overflows.get(&first_extra) {
+                return Err(ParseError::TooManyArgumentsForOption(
+                    token_idx,
+                    *opt_idx,
+                    remaining_args,
+                ));
+            }
+            return Err(ParseError::TooManyArguments(token_idx, remaining_args));
+        }
+
+        Ok(())
+    }
+    fn parse_positional_values(
         arg_def: &CliArgument,
-        current_token: String,
-        tokens: &mut Peekable<std::vec::IntoIter<String>>,
+        values: Vec<(usize, String)>,
     ) -> Result<Box<dyn Any>, ParseError> {
+        let (token_indexes, mut values): (Vec<usize>, Vec<String>) = values.into_iter().unzip();
+
         if arg_def.variadic {
-            let mut values = vec![current_token];
-            while tokens.peek().is_some() && !Self::is_option_token(tokens.peek().unwrap()) {
-                values.push(tokens.next().unwrap());
+            if let Some(delimiter) = arg_def.delimiter {
+                values = Self::split_delimited(&values[0], delimiter);
             }
+            // Extra values are blamed on the first one past the maximum
+            let token_idx = arg_def
+                .max_values
+                .and_then(|max| token_indexes.get(max))
+                .unwrap_or(&token_indexes[0]);
+            Self::check_value_count(arg_def, values.len(), *token_idx)?;
+        }
+        Self::check_choices(arg_def, &values, &token_indexes)?;
 
+        if arg_def.variadic || arg_def.arity.is_some() {
             Ok(Box::new(values))
         } else {
-            Ok(Box::new(current_token))
+            Ok(Box::new(values.remove(0)))
+        }
+    }
+    // Splits `KEY=VALUE` tokens on the first `=` and appends them to the option's entries
+    fn insert_map_entry(
+        parsed_opts: &mut ParsedOpts,
+        opt_def: &CliOption,
+        opt_idx: usize,
+        token_idx: usize,
+        token: String,
+    ) -> Result<(), ParseError> {
+        let parsed_value = parsed_opts.get_mut(&opt_def.name).unwrap();
+        if parsed_value
+            .downcast_ref::<Vec<(String, String)>>()
+            .is_none()
+        {
+            *parsed_value = Box::new(Vec::<(String, String)>::new());
+        }
+        let entries = parsed_value
+            .downcast_mut::<Vec<(String, String)>>()
+            .unwrap();
+
+        let raw_entries = match opt_def.args[0].delimiter {
+            Some(delimiter) => Self::split_delimited(&token, delimiter),
+            None => vec![token],
+        };
+
+        for raw_entry in raw_entries {
+            let (key, value) = match raw_entry.split_once('=') {
+                Some((key, value))
+                    if !key.is_empty()
+                        && opt_def.args[0]
+                            .map_entry_check
+                            .is_none_or(|check| check(key, value)) =>
+                {
+                    (key.to_owned(), value.to_owned())
+                }
+                _ => return Err(ParseError::InvalidMapEntry(token_idx, opt_idx, raw_entry)),
+            };
+
+            if entries.iter().any(|(existing, _)| *existing == key) {
+                return Err(ParseError::DuplicateMapKey(token_idx, opt_idx, key));
+            }
+            entries.push((key, value));
+        }
+
+        Ok(())
+    }
+    // Empty segments, e.g. from `a,,b` or a trailing delimiter, are dropped
+    fn split_delimited(token: &str, delimiter: char) -> Vec<String> {
+        token
+            .split(delimiter)
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_owned())
+            .collect()
+    }
+    // Delimited values accumulate across repeated occurrences of the option
+    fn extend_delimited_values(
+        parsed_opts: &mut ParsedOpts,
+        opt_name: &str,
+        parsed_value: Box<dyn Any>,
+    ) {
+        let values = parsed_value.downcast::<Vec<String>>().unwrap();
+        let existing = parsed_opts.get_mut(opt_name).unwrap();
+
+        match existing.downcast_mut::<Vec<String>>() {
+            Some(existing) => existing.extend(*values),
+            None => *existing = values,
         }
     }
 
@@ -177,7 +757,8 @@ impl Cli {
     // ------------------------------------------------------------
     fn check_for_missing_required_opts(
         parsed_opts: &ParsedOpts,
-        template_opts: &Vec<CliOption>,
+        template_opts: &[CliOption],
+        end: usize,
     ) -> Result<(), ParseError> {
         let required_opts = template_opts.iter().filter(|opt| !opt.optional);
 
@@ -207,7 +788,16 @@ impl Cli {
                 }
                 1 => {
                     let arg_def = &opt.args[0];
-                    if arg_def.variadic {
+                    if arg_def.map {
+                        if parsed_opts
+                            .get(&opt.name.clone())
+                            .unwrap()
+                            .downcast_ref::<Vec<(String, String)>>()
+                            .is_none()
+                        {
+                            missing_required_opts.push(flags);
+                        }
+                    } else if arg_def.is_multi_valued() {
                         if parsed_opts
                             .get(&opt.name.clone())
                             .unwrap()
@@ -241,46 +831,136 @@ impl Cli {
         }
 
         if !missing_required_opts.is_empty() {
-            return Err(ParseError::MissingRequiredOptions(missing_required_opts));
+            return Err(ParseError::MissingRequiredOptions(
+                end,
+                missing_required_opts,
+            ));
+        }
+
+        Ok(())
+    }
+    // Delimited option values are counted once all occurrences are collected
+    fn check_delimited_value_counts(
+        parsed_opts: &ParsedOpts,
+        template_opts: &[CliOption],
+        opt_tokens: &HashMap<String, usize>,
+    ) -> Result<(), ParseError> {
+        for opt in template_opts.iter().filter(|opt| opt.args.len() == 1) {
+            let arg_def = &opt.args[0];
+            if arg_def.delimiter.is_none() || arg_def.map {
+                continue;
+            }
+            if let Some(values) = parsed_opts[&opt.name].downcast_ref::<Vec<String>>() {
+                Self::check_value_count(arg_def, values.len(), opt_tokens[&opt.name])?;
+            }
+        }
+        Ok(())
+    }
+    // `token_indexes` has one index per value, except for delimited values which share a token
+    fn check_choices(
+        arg_def: &CliArgument,
+        values: &[String],
+        token_indexes: &[usize],
+    ) -> Result<(), ParseError> {
+        if arg_def.choices.is_empty() {
+            return Ok(());
         }
 
+        match values
+            .iter()
+            .position(|value| !arg_def.choices.contains(value))
+        {
+            Some(position) => Err(ParseError::InvalidChoice(
+                *token_indexes
+                    .get(position)
+                    .unwrap_or(&token_indexes[token_indexes.len() - 1]),
+                arg_def.reconstruct_name(),
+                values[position].clone(),
+                arg_def.choices.clone(),
+            )),
+            None => Ok(()),
+        }
+    }
+    fn check_value_count(
+        arg_def: &CliArgument,
+        count: usize,
+        token_idx: usize,
+    ) -> Result<(), ParseError> {
+        if let Some(min) = arg_def.min_values
+            && count < min
+        {
+            return Err(ParseError::TooFewValues(
+                token_idx,
+                arg_def.reconstruct_name(),
+                min,
+                count,
+            ));
+        }
+        if let Some(max) = arg_def.max_values
+            && count > max
+        {
+            return Err(ParseError::TooManyValues(
+                token_idx,
+                arg_def.reconstruct_name(),
+                max,
+                count,
+            ));
+        }
         Ok(())
     }
     fn check_for_missing_required_args(
-        template_args: &Vec<CliArgument>,
-        positional_idx: usize,
-        opt_idx: Option<usize>,
+        template_args: &[CliArgument],
+        arg_idx: usize,
+        opt_idx: usize,
+        opt_token: usize,
     ) -> Result<(), ParseError> {
-        if positional_idx < template_args.iter().filter(|arg| !arg.optional).count() {
-            let missing_args = template_args[positional_idx..]
+        if arg_idx < template_args.iter().filter(|arg| !arg.optional).count() {
+            let missing_args = template_args[arg_idx..]
                 .iter()
                 .filter(|arg| !arg.optional)
                 .map(|arg| arg.reconstruct_name())
                 .collect::<Vec<String>>();
-            if let Some(opt_idx) = opt_idx {
-                return Err(ParseError::MissingRequiredArgumentsForOption(
-                    opt_idx,
-                    missing_args,
-                ));
-            } else {
-                return Err(ParseError::MissingRequiredArguments(missing_args));
-            }
+            return Err(ParseError::MissingRequiredArgumentsForOption(
+                opt_token,
+                opt_idx,
+                missing_args,
+            ));
         }
         Ok(())
     }
     // ------------------------------------------------------------
     // Boolean Utils
     // ------------------------------------------------------------
+    // Whether the argument takes every token that isn't claimed by another positional
+    fn takes_many_tokens(arg_def: &CliArgument) -> bool {
+        arg_def.variadic && arg_def.delimiter.is_none()
+    }
     fn is_option_token(token: &str) -> bool {
         token.starts_with('-') && token != "-"
     }
+    // Whether the next token is a value, values from the environment never start an option
+    fn next_is_value(tokens: &mut Tokens, from_env: bool) -> bool {
+        tokens
+            .peek()
+            .is_some_and(|(_, token)| from_env || !Self::is_option_token(token))
+    }
+    // Number of tokens a positional argument needs to be satisfied
+    fn min_positional_tokens(arg_def: &CliArgument) -> usize {
+        if arg_def.optional {
+            0
+        } else if Self::takes_many_tokens(arg_def) {
+            arg_def.min_values.unwrap_or(1).max(1)
+        } else {
+            arg_def.arity.unwrap_or(1)
+        }
+    }
     // ------------------------------------------------------------
     // Initialization Utils
     // ------------------------------------------------------------
     fn initialize_parsed_args(template_args: &Vec<CliArgument>) -> ParsedArgs {
         let mut parsed_args: ParsedArgs = HashMap::new();
         for arg in template_args {
-            match arg.variadic {
+            match arg.is_multi_valued() {
                 true => parsed_args.insert(arg.name.clone(), Box::new(None::<Vec<String>>)),
                 false => parsed_args.insert(arg.name.clone(), Box::new(None::<String>)),
             };
@@ -296,7 +976,10 @@ impl Cli {
                 }
                 1 => {
                     let arg_def = &opt.args[0];
-                    if arg_def.variadic {
+                    if arg_def.map {
+                        parsed_opts
+                            .insert(opt.name.clone(), Box::new(None::<Vec<(String, String)>>));
+                    } else if arg_def.is_multi_valued() {
                         parsed_opts.insert(opt.name.clone(), Box::new(None::<Vec<String>>));
                     } else {
                         parsed_opts.insert(opt.name.clone(), Box::new(None::<String>));
@@ -0,0 +1,63 @@
+use std::{any::Any, collections::HashMap};
+
+use crate::{Cli, CliCommand, locale::Message};
+
+impl Cli {
+    // The command to run for a name given on the command line. Warns when the command is
+    // deprecated and swaps an old name for the command that replaced it.
+    pub(crate) fn resolve_command(&self, name: &str) -> String {
+        if let Some(note) = self
+            .commands
+            .iter()
+            .find(|cmd| cmd.name == name)
+            .and_then(|cmd| cmd.deprecated.as_ref())
+        {
+            self.warn(&self.message(Message::DeprecatedCommand(name, note)));
+        }
+        self.replacement_command(name)
+    }
+    // The command an old name stands for, without warning, e.g. for `help <old name>`
+    pub(crate) fn replacement_command(&self, name: &str) -> String {
+        self.commands
+            .iter()
+            .find(|cmd| cmd.name == name)
+            .and_then(|cmd| cmd.replaced_by.clone())
+            .unwrap_or_else(|| name.to_owned())
+    }
+    // Warns once for every deprecated option on the command line, with the flag as it was written
+    pub(crate) fn warn_deprecated_options(&self, command_def: &CliCommand, env_args: &[String]) {
+        let mut warned = Vec::new();
+        for token in env_args {
+            let Some(opt_def) = command_def.options.iter().find(|opt| opt.flags == *token) else {
+                continue;
+            };
+            if let Some(note) = &opt_def.deprecated
+                && !warned.contains(&opt_def.name)
+            {
+                warned.push(opt_def.name.clone());
+                self.warn(&self.message(Message::DeprecatedOption(token, note)));
+            }
+        }
+    }
+    // Warns for every deprecated positional argument that received a value
+    pub(crate) fn warn_deprecated_arguments(
+        &self,
+        command_def: &CliCommand,
+        parsed_args: &HashMap<String, Box<dyn Any>>,
+    ) {
+        for arg_def in &command_def.arguments {
+            let Some(note) = &arg_def.deprecated else {
+                continue;
+            };
+            // Arguments without a value keep the `None` they were initialized with
+            let value = &parsed_args[&arg_def.name];
+            if value.is::<Option<String>>() || value.is::<Option<Vec<String>>>() {
+                continue;
+            }
+            self.warn(&self.message(Message::DeprecatedArgument(
+                &arg_def.reconstruct_name(),
+                note,
+            )));
+        }
+    }
+}
@@ -1,6 +1,10 @@
 use std::{any::Any, collections::HashMap};
 
+mod autocorrect;
 pub mod cli;
+mod deprecation;
+
+pub use cli::ParseExit;
 
 pub trait FromParsed {
     fn from_parsed(parsed: HashMap<String, Box<dyn Any>>) -> Self;
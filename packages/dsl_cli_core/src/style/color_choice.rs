@@ -0,0 +1,40 @@
+// Disables colors when set to any non-empty value, see https://no-color.org
+pub const NO_COLOR_ENV: &str = "NO_COLOR";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    // Colors are used when the stream is a terminal and `NO_COLOR` isn't set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+impl ColorChoice {
+    // Parses the value of `--color=<when>`
+    pub fn from_flag_value(value: &str) -> Option<Self> {
+        match value {
+            "auto" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None,
+        }
+    }
+    // Whether to use colors on a stream, knowing if it is a terminal
+    pub fn use_colors_on(&self, is_terminal: bool) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                let no_color = std::env::var_os(NO_COLOR_ENV).is_some_and(|v| !v.is_empty());
+                !no_color && is_terminal
+            }
+        }
+    }
+}
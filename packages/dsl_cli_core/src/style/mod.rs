@@ -0,0 +1,5 @@
+mod color_choice;
+mod theme;
+
+pub use color_choice::{ColorChoice, Stream};
+pub use theme::{Color, Style, Theme};
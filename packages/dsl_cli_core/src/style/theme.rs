@@ -0,0 +1,150 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    fn ansi_code(&self) -> u8 {
+        match self {
+            Color::Black => 30,
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::White => 37,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bold: bool,
+    pub dim: bool,
+    pub underline: bool,
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn fg(mut self, color: Color) -> Self {
+        self.fg = Some(color);
+        self
+    }
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+    pub fn dim(mut self) -> Self {
+        self.dim = true;
+        self
+    }
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+    // Wraps the text in ANSI escape codes, a plain style returns the text unchanged
+    pub fn paint(&self, text: &str) -> String {
+        let mut codes = Vec::new();
+        if self.bold {
+            codes.push(1);
+        }
+        if self.dim {
+            codes.push(2);
+        }
+        if self.underline {
+            codes.push(4);
+        }
+        if let Some(fg) = self.fg {
+            codes.push(fg.ansi_code());
+        }
+
+        if codes.is_empty() || text.is_empty() {
+            return text.to_owned();
+        }
+
+        let codes = codes
+            .iter()
+            .map(|code| code.to_string())
+            .collect::<Vec<String>>()
+            .join(";");
+
+        format!("\x1b[{}m{}\x1b[0m", codes, text)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Theme {
+    // Section headers, e.g. `Usage:`, `Options:`
+    pub header: Style,
+    // Option flags, e.g. `-f, --force`
+    pub flag: Style,
+    // Value placeholders, e.g. `<file>`, `[options]`
+    pub placeholder: Style,
+    // Command names in the command list
+    pub command: Style,
+    // The `error:` prefix
+    pub error: Style,
+    // The `tip:` prefix
+    pub tip: Style,
+    // The `warning:` prefix
+    pub warning: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            header: Style::new().bold().underline(),
+            flag: Style::new().bold().fg(Color::Cyan),
+            placeholder: Style::new().fg(Color::Cyan),
+            command: Style::new().bold(),
+            error: Style::new().bold().fg(Color::Red),
+            tip: Style::new().bold().fg(Color::Green),
+            warning: Style::new().bold().fg(Color::Yellow),
+        }
+    }
+}
+
+impl Theme {
+    // A theme without any styling, used when colors are disabled
+    pub fn plain() -> Self {
+        Self {
+            header: Style::new(),
+            flag: Style::new(),
+            placeholder: Style::new(),
+            command: Style::new(),
+            error: Style::new(),
+            tip: Style::new(),
+            warning: Style::new(),
+        }
+    }
+    // Styles the flags and placeholders of an entry like `-f, --format <format>`
+    pub(crate) fn paint_entry(&self, entry: &str) -> String {
+        entry
+            .split(' ')
+            .map(|word| {
+                if word.starts_with('-') {
+                    match word.strip_suffix(',') {
+                        Some(flag) => self.flag.paint(flag) + ",",
+                        None => self.flag.paint(word),
+                    }
+                } else if word.starts_with('<') || word.starts_with('[') {
+                    self.placeholder.paint(word)
+                } else {
+                    word.to_owned()
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    cell::RefCell,
+    io::{self, IsTerminal, Write},
+    rc::Rc,
+};
+
+use crate::style::Stream;
+
+// Where help and error output goes, see `Cli::set_terminal`. Help is written to `stdout`,
+// errors, warnings and prompts to `stderr`.
+pub trait Terminal {
+    fn stdout(&mut self) -> &mut dyn Write;
+    fn stderr(&mut self) -> &mut dyn Write;
+    // Whether `stream` is shown on an interactive terminal, which enables colors (with
+    // `ColorChoice::Auto`) and the pager
+    fn is_terminal(&self, _stream: Stream) -> bool {
+        false
+    }
+}
+
+// The process stdout and stderr, used by default
+pub struct StdTerminal {
+    stdout: io::Stdout,
+    stderr: io::Stderr,
+}
+
+impl StdTerminal {
+    pub fn new() -> Self {
+        Self {
+            stdout: io::stdout(),
+            stderr: io::stderr(),
+        }
+    }
+}
+
+impl Default for StdTerminal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Terminal for StdTerminal {
+    fn stdout(&mut self) -> &mut dyn Write {
+        &mut self.stdout
+    }
+    fn stderr(&mut self) -> &mut dyn Write {
+        &mut self.stderr
+    }
+    fn is_terminal(&self, stream: Stream) -> bool {
+        match stream {
+            Stream::Stdout => self.stdout.is_terminal(),
+            Stream::Stderr => self.stderr.is_terminal(),
+        }
+    }
+}
+
+// A pair of writers, never treated as a terminal, see `Cli::set_output`
+impl<O: Write, E: Write> Terminal for (O, E) {
+    fn stdout(&mut self) -> &mut dyn Write {
+        &mut self.0
+    }
+    fn stderr(&mut self) -> &mut dyn Write {
+        &mut self.1
+    }
+}
+
+// An in-memory writer whose clones share the same buffer, so output given to the Cli can still
+// be read afterwards (useful for snapshot tests)
+#[derive(Debug, Clone, Default)]
+pub struct CapturedOutput(Rc<RefCell<Vec<u8>>>);
+
+impl CapturedOutput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    // Everything written so far, invalid UTF-8 is replaced
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.borrow()).into_owned()
+    }
+}
+
+impl Write for CapturedOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -1,9 +1,38 @@
+use super::{PlaceholderStyle, ValueHint, placeholder::value_type_name};
+
 #[derive(Debug, Clone)]
 pub struct CliArgument {
     pub(crate) name: String,
     pub(crate) description: Option<String>,
     pub(crate) optional: bool,
     pub(crate) variadic: bool,
+    pub(crate) map: bool,
+    // Whether a map entry parses into the key and value types, set by the generated code
+    pub(crate) map_entry_check: Option<fn(&str, &str) -> bool>,
+    pub(crate) delimiter: Option<char>,
+    pub(crate) arity: Option<usize>,
+    // Whether the value at a position of a tuple or array parses into its element type, set by
+    // the generated code
+    pub(crate) element_check: Option<fn(usize, &str) -> bool>,
+    pub(crate) min_values: Option<usize>,
+    pub(crate) max_values: Option<usize>,
+    // The Rust type the value is parsed into, e.g. `Option<u32>`
+    pub(crate) type_name: Option<String>,
+    // Only used for help, the value itself is filled in by the generated code
+    pub(crate) default: Option<String>,
+    // The only values accepted, empty when any value is accepted
+    pub(crate) choices: Vec<String>,
+    pub(crate) section: Option<String>,
+    // Replaces the placeholder derived from the name, e.g. `FILE`
+    pub(crate) value_name: Option<String>,
+    // Derived from the type when not set, see `value_hint()`
+    pub(crate) value_hint: Option<ValueHint>,
+    // Left out of the help, but still usable
+    pub(crate) hidden: bool,
+    // Printed as a warning when a value is given, also marks it in the help
+    pub(crate) deprecated: Option<String>,
+    // Set by the `Cli` the argument belongs to
+    pub(crate) placeholder_style: PlaceholderStyle,
 }
 
 impl CliArgument {
@@ -16,14 +45,164 @@ impl CliArgument {
         Self {
             name: arg_name,
             description: description.map(|d| d.into()),
-            optional: optional,
-            variadic: variadic,
+            optional,
+            variadic,
+            map: false,
+            map_entry_check: None,
+            delimiter: None,
+            arity: None,
+            element_check: None,
+            min_values: None,
+            max_values: None,
+            type_name: None,
+            default: None,
+            choices: Vec::new(),
+            section: None,
+            value_name: None,
+            value_hint: None,
+            hidden: false,
+            deprecated: None,
+            placeholder_style: PlaceholderStyle::default(),
+        }
+    }
+    // Marks the argument as a `KEY=VALUE` map (HashMap<K, V> / BTreeMap<K, V>)
+    pub fn set_map(&mut self, map: bool) -> &mut Self {
+        self.map = map;
+        self
+    }
+    // Rejects map entries whose key or value doesn't parse, instead of failing after parsing
+    pub fn set_map_entry_check(&mut self, check: Option<fn(&str, &str) -> bool>) -> &mut Self {
+        self.map_entry_check = check;
+        self
+    }
+    // Splits every provided token on the delimiter instead of consuming the following tokens
+    pub fn set_delimiter(&mut self, delimiter: Option<char>) -> &mut Self {
+        self.delimiter = delimiter;
+        self
+    }
+    // Rejects tuple and array values that don't parse, instead of failing after parsing
+    pub fn set_element_check(&mut self, check: Option<fn(usize, &str) -> bool>) -> &mut Self {
+        self.element_check = check;
+        self
+    }
+    // Consumes exactly `arity` values (tuple and array types)
+    pub fn set_arity(&mut self, arity: Option<usize>) -> &mut Self {
+        self.arity = arity;
+        self
+    }
+    // Lower bound on the number of values of a variadic argument
+    pub fn set_min_values(&mut self, min_values: Option<usize>) -> &mut Self {
+        self.min_values = min_values;
+        self
+    }
+    // Upper bound on the number of values of a variadic argument
+    pub fn set_max_values(&mut self, max_values: Option<usize>) -> &mut Self {
+        self.max_values = max_values;
+        self
+    }
+    pub fn set_type_name(&mut self, type_name: impl Into<String>) -> &mut Self {
+        self.type_name = Some(type_name.into());
+        self
+    }
+    pub fn set_default(&mut self, default: Option<impl Into<String>>) -> &mut Self {
+        self.default = default.map(|d| d.into());
+        self
+    }
+    pub fn set_choices(&mut self, choices: &[&str]) -> &mut Self {
+        self.choices = choices.iter().map(|c| c.to_string()).collect();
+        self
+    }
+    // Heading the argument is listed under in the help
+    pub fn set_section(&mut self, section: Option<impl Into<String>>) -> &mut Self {
+        self.section = section.map(|s| s.into());
+        self
+    }
+    pub fn set_value_name(&mut self, value_name: Option<impl Into<String>>) -> &mut Self {
+        self.value_name = value_name.map(|v| v.into());
+        self
+    }
+    // Overrides the hint derived from the type, e.g. `Dir` for a path that must be a directory
+    pub fn set_value_hint(&mut self, value_hint: Option<ValueHint>) -> &mut Self {
+        self.value_hint = value_hint;
+        self
+    }
+    pub fn set_hidden(&mut self, hidden: bool) -> &mut Self {
+        self.hidden = hidden;
+        self
+    }
+    // e.g. `pass the file with --input instead`
+    pub fn set_deprecated(&mut self, note: Option<impl Into<String>>) -> &mut Self {
+        self.deprecated = note.map(|n| n.into());
+        self
+    }
+    pub(crate) fn set_placeholder_style(&mut self, style: PlaceholderStyle) {
+        self.placeholder_style = style;
+    }
+    // The text inside `<>` for the value at `position` (tuple elements can have different types)
+    pub(crate) fn placeholder(&self, position: usize) -> String {
+        if let Some(value_name) = &self.value_name {
+            return value_name.clone();
         }
+
+        let style = self.placeholder_style;
+        let placeholder = style.apply(&self.name, self.type_name.as_deref(), position);
+        if self.map && style != PlaceholderStyle::TypeName {
+            return placeholder + "=" + &style.apply("value", None, position);
+        }
+        placeholder
+    }
+    // What the completion scripts offer for the value, files for `PathBuf` unless set otherwise
+    pub(crate) fn value_hint(&self) -> ValueHint {
+        if let Some(value_hint) = self.value_hint {
+            return value_hint;
+        }
+        match self.type_name.as_deref().map(|ty| value_type_name(ty, 0)) {
+            Some(ty) if ty == "PathBuf" => ValueHint::File,
+            _ => ValueHint::Text,
+        }
+    }
+    // Whether the parsed value is a list of values rather than a single one
+    pub(crate) fn is_multi_valued(&self) -> bool {
+        self.variadic || self.arity.is_some()
     }
     pub fn reconstruct_name(&self) -> String {
-        let mut name = self.name.clone();
+        let mut name = self.placeholder(0);
+
+        if let Some(arity) = self.arity {
+            let placeholders = (0..arity)
+                .map(|position| self.placeholder(position))
+                .collect::<Vec<String>>();
+            return if self.optional {
+                format!("[{}]", placeholders.join(" "))
+            } else {
+                placeholders
+                    .iter()
+                    .map(|name| format!("<{}>", name))
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            };
+        }
+
+        // Bounded variadics show their value count instead of `...`, e.g. <file>{2,4}
+        let bounds = match (self.min_values, self.max_values) {
+            (None, None) => None,
+            (min, max) => Some(format!(
+                "{{{},{}}}",
+                min.unwrap_or(1),
+                max.map(|max| max.to_string()).unwrap_or_default()
+            )),
+        };
+
+        if let Some(delimiter) = self.delimiter {
+            name = if self.optional {
+                format!("[{}[{}{}...]]", name, delimiter, name)
+            } else {
+                format!("<{}>[{}<{}>...]", name, delimiter, name)
+            };
+            return name + &bounds.unwrap_or_default();
+        }
 
-        if self.variadic {
+        if self.variadic && bounds.is_none() {
             name += "...";
         }
 
@@ -33,6 +212,6 @@ impl CliArgument {
             name = format!("<{}>", name);
         }
 
-        name
+        name + &bounds.unwrap_or_default()
     }
 }
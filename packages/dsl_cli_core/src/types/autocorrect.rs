@@ -0,0 +1,23 @@
+// What to do when a mistyped command has a single close match, see `Cli::set_autocorrect`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Autocorrect {
+    // Only print the suggestion
+    #[default]
+    Off,
+    // Ask `Did you mean `status`? [y/N]` and run the command if the answer is yes
+    Prompt,
+    // Print a warning and run the command after the autocorrect delay
+    Run,
+}
+
+impl Autocorrect {
+    // Parses the value of the `<APP>_AUTOCORRECT` environment variable
+    pub fn from_env_value(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "off" => Some(Autocorrect::Off),
+            "prompt" => Some(Autocorrect::Prompt),
+            "run" => Some(Autocorrect::Run),
+            _ => None,
+        }
+    }
+}
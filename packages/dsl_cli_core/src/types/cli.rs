@@ -1,5 +1,16 @@
-use super::CliCommand;
+use super::{Autocorrect, CliCommand, HelpText, PlaceholderStyle};
+use crate::error::{ErrorFormat, ParseErrorKind};
+use crate::exit_code::ExitCodePolicy;
+use crate::help::{HelpOrder, HelpRenderer};
+use crate::locale::{Message, MessageCatalog, Messages, env_locale, normalize_locale};
+use crate::style::{ColorChoice, Stream, Theme};
+use crate::terminal::{StdTerminal, Terminal};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
 use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
 
 pub struct Cli {
     pub(crate) name: String,
@@ -9,6 +20,46 @@ pub struct Cli {
     // Useful for error messages
     pub(crate) executable_name: String,
     pub(crate) used_command: Option<String>,
+    // The first token when it wasn't a command and `cli` was used instead, for typo hints
+    pub(crate) unmatched_command: Option<String>,
+    // The tokens given to the last parse, reprinted under errors
+    pub(crate) invocation: Vec<String>,
+    // Environment variables read for options as (variable, value), their token indexes follow
+    // the invocation: the first one is `invocation.len() + 1`
+    pub(crate) env_values: Vec<(String, String)>,
+    // Usage line of the built-in command being run (e.g. `completions`), shown under its errors
+    pub(crate) builtin_usage: Option<String>,
+    // Fixed help width, takes priority over the terminal width
+    pub(crate) help_width: Option<usize>,
+    pub(crate) color: ColorChoice,
+    pub(crate) theme: Theme,
+    // Replaces the plain-text help output when set
+    pub(crate) help_renderer: Option<Box<dyn HelpRenderer>>,
+    pub(crate) help_order: HelpOrder,
+    // Lists hidden commands, options and arguments in the help, set by `help --hidden`
+    pub(crate) show_hidden: bool,
+    // Adds the built-in `completions <shell>` command
+    pub(crate) completions_command: bool,
+    pub(crate) help_text: HelpText,
+    pub(crate) placeholder_style: PlaceholderStyle,
+    // Long help goes through `$PAGER` when it doesn't fit on the terminal
+    pub(crate) pager: bool,
+    // Overridden by the `<APP>_AUTOCORRECT` environment variable
+    pub(crate) autocorrect: Autocorrect,
+    // How long `Autocorrect::Run` waits before running the corrected command
+    pub(crate) autocorrect_delay: Duration,
+    // Where the `Autocorrect::Prompt` answer is read from, stdin when not set
+    pub(crate) autocorrect_input: Option<Box<dyn BufRead>>,
+    // Overridden by the `DSL_CLI_ERROR_FORMAT` environment variable
+    pub(crate) error_format: ErrorFormat,
+    // Every help and error message is written through it, a RefCell so `&self` methods can print
+    pub(crate) terminal: RefCell<Box<dyn Terminal>>,
+    pub(crate) exit_code_policy: ExitCodePolicy,
+    pub(crate) exit_code_overrides: HashMap<ParseErrorKind, i32>,
+    // Taken from `LC_ALL`, `LC_MESSAGES` or `LANG` when not set
+    pub(crate) locale: Option<String>,
+    // Message catalogs by locale, e.g. `de` or `pt_BR`
+    pub(crate) translations: Vec<(String, Rc<dyn MessageCatalog>)>,
 }
 
 impl Cli {
@@ -28,14 +79,233 @@ impl Cli {
             commands: Vec::new(),
             executable_name,
             used_command: None,
+            unmatched_command: None,
+            invocation: Vec::new(),
+            env_values: Vec::new(),
+            builtin_usage: None,
+            help_width: None,
+            color: ColorChoice::Auto,
+            theme: Theme::default(),
+            help_renderer: None,
+            help_order: HelpOrder::Declaration,
+            show_hidden: false,
+            completions_command: false,
+            help_text: HelpText::default(),
+            placeholder_style: PlaceholderStyle::default(),
+            pager: true,
+            autocorrect: Autocorrect::Off,
+            autocorrect_delay: Duration::from_secs(2),
+            autocorrect_input: None,
+            error_format: ErrorFormat::Text,
+            terminal: RefCell::new(Box::new(StdTerminal::new())),
+            exit_code_policy: ExitCodePolicy::Gnu,
+            exit_code_overrides: HashMap::new(),
+            locale: None,
+            translations: Vec::new(),
         }
     }
+    pub fn set_long_about(&mut self, long_about: Option<impl Into<String>>) -> &mut Self {
+        self.help_text.long_about = long_about.map(|l| l.into());
+        self
+    }
+    pub fn set_before_help(&mut self, before_help: Option<impl Into<String>>) -> &mut Self {
+        self.help_text.before_help = before_help.map(|b| b.into());
+        self
+    }
+    pub fn set_after_help(&mut self, after_help: Option<impl Into<String>>) -> &mut Self {
+        self.help_text.after_help = after_help.map(|a| a.into());
+        self
+    }
+    pub fn add_example(
+        &mut self,
+        command: impl Into<String>,
+        description: impl Into<String>,
+    ) -> &mut Self {
+        self.help_text
+            .examples
+            .push((command.into(), description.into()));
+        self
+    }
+    // Wraps help at a fixed width instead of the terminal width (useful for tests)
+    pub fn set_help_width(&mut self, width: usize) -> &mut Self {
+        self.help_width = Some(width);
+        self
+    }
+    // Overridden by `--color=always|never|auto` on the command line
+    pub fn set_color(&mut self, color: ColorChoice) -> &mut Self {
+        self.color = color;
+        self
+    }
+    pub fn set_theme(&mut self, theme: Theme) -> &mut Self {
+        self.theme = theme;
+        self
+    }
+    // Sorts options and commands alphabetically in the help instead of keeping their declaration order
+    pub fn set_help_order(&mut self, order: HelpOrder) -> &mut Self {
+        self.help_order = order;
+        self
+    }
+    // The name shown in usage lines and used by completion scripts, taken from `argv[0]` when
+    // not set. Useful when generating completions from a build script.
+    pub fn set_executable_name(&mut self, name: impl Into<String>) -> &mut Self {
+        self.executable_name = name.into();
+        self
+    }
+    // Lists hidden items in the help and the help search, like `help --hidden` does
+    pub fn set_show_hidden(&mut self, show_hidden: bool) -> &mut Self {
+        self.show_hidden = show_hidden;
+        self
+    }
+    // Adds a `completions <shell>` command that prints the script of `generate_completion`. A
+    // command named `completions` takes priority over it.
+    pub fn set_completions_command(&mut self, enabled: bool) -> &mut Self {
+        self.completions_command = enabled;
+        self
+    }
+    // How placeholders are written for arguments without a `value_name`, in help and errors
+    pub fn set_placeholder_style(&mut self, style: PlaceholderStyle) -> &mut Self {
+        self.placeholder_style = style;
+        for cmd in &mut self.commands {
+            cmd.set_placeholder_style(style);
+        }
+        self
+    }
+    // Also disabled by the `DSL_CLI_NO_PAGER` environment variable
+    pub fn set_pager(&mut self, pager: bool) -> &mut Self {
+        self.pager = pager;
+        self
+    }
+    // Acts on a single close match when the command is mistyped, instead of only suggesting it
+    pub fn set_autocorrect(&mut self, autocorrect: Autocorrect) -> &mut Self {
+        self.autocorrect = autocorrect;
+        self
+    }
+    pub fn set_autocorrect_delay(&mut self, delay: Duration) -> &mut Self {
+        self.autocorrect_delay = delay;
+        self
+    }
+    // Reads the prompt answer from `input` instead of stdin (useful for tests)
+    pub fn set_autocorrect_input(&mut self, input: impl BufRead + 'static) -> &mut Self {
+        self.autocorrect_input = Some(Box::new(input));
+        self
+    }
+    // `ErrorFormat::Json` reports parse errors as a JSON object, for tools wrapping the CLI
+    pub fn set_error_format(&mut self, format: ErrorFormat) -> &mut Self {
+        self.error_format = format;
+        self
+    }
+    // Sends help and error output somewhere else than the process stdout and stderr
+    pub fn set_terminal(&mut self, terminal: impl Terminal + 'static) -> &mut Self {
+        self.terminal = RefCell::new(Box::new(terminal));
+        self
+    }
+    // Shorthand for `set_terminal` with a pair of writers, e.g. two `CapturedOutput`s in tests
+    pub fn set_output(
+        &mut self,
+        stdout: impl Write + 'static,
+        stderr: impl Write + 'static,
+    ) -> &mut Self {
+        self.set_terminal((stdout, stderr))
+    }
+    // The exit code of parse errors without an override, 2 (GNU) by default
+    pub fn set_exit_code_policy(&mut self, policy: ExitCodePolicy) -> &mut Self {
+        self.exit_code_policy = policy;
+        self
+    }
+    // Overrides the exit code of one kind of parse error
+    pub fn set_exit_code(&mut self, kind: ParseErrorKind, code: i32) -> &mut Self {
+        self.exit_code_overrides.insert(kind, code);
+        self
+    }
+    // Translates the built-in help and error messages for `locale`, e.g. `de` or `pt_BR`
+    pub fn add_translation(
+        &mut self,
+        locale: impl Into<String>,
+        catalog: impl MessageCatalog + 'static,
+    ) -> &mut Self {
+        let locale = normalize_locale(&locale.into());
+        self.translations.push((locale, Rc::new(catalog)));
+        self
+    }
+    // Uses `locale` instead of the one from the environment
+    pub fn set_locale(&mut self, locale: impl Into<String>) -> &mut Self {
+        self.locale = Some(locale.into());
+        self
+    }
+    // The messages for the current locale. A catalog for the language alone (`de`) is used
+    // when there is none for the region (`de_AT`).
+    pub fn messages(&self) -> Messages {
+        let Some(locale) = self
+            .locale
+            .as_deref()
+            .map(normalize_locale)
+            .or_else(env_locale)
+        else {
+            return Messages::default();
+        };
+        let language = locale.split('_').next().unwrap_or_default();
+
+        let find = |wanted: &str| {
+            self.translations
+                .iter()
+                .find(|(locale, _)| locale.eq_ignore_ascii_case(wanted))
+                .map(|(_, catalog)| catalog.clone())
+        };
+        Messages::new(find(&locale).or_else(|| find(language)))
+    }
+    pub(crate) fn message(&self, message: Message) -> String {
+        self.messages().get(message)
+    }
+    pub fn set_help_renderer(&mut self, renderer: impl HelpRenderer + 'static) -> &mut Self {
+        self.help_renderer = Some(Box::new(renderer));
+        self
+    }
+    // The theme to use for output written to `stream`, plain if colors are disabled
+    pub(crate) fn theme_for(&self, stream: Stream) -> Theme {
+        if self.color.use_colors_on(self.is_terminal(stream)) {
+            self.theme.clone()
+        } else {
+            Theme::plain()
+        }
+    }
+    pub(crate) fn is_terminal(&self, stream: Stream) -> bool {
+        self.terminal.borrow().is_terminal(stream)
+    }
+    pub(crate) fn write_to(&self, stream: Stream, text: &str) {
+        let mut terminal = self.terminal.borrow_mut();
+        let out = match stream {
+            Stream::Stdout => terminal.stdout(),
+            Stream::Stderr => terminal.stderr(),
+        };
+        // There is nobody left to tell when the output is gone (e.g. a closed pipe)
+        let _ = out.write_all(text.as_bytes()).and_then(|_| out.flush());
+    }
+    // The command matched by the last parse, `cli` when the top-level command was used
+    pub fn used_command(&self) -> Option<&str> {
+        self.used_command.as_deref()
+    }
+    // Every command name to suggest, including the built-in `help`. Hidden commands and old
+    // names of replaced commands still work, but aren't listed.
+    pub(crate) fn command_names(&self) -> Vec<String> {
+        self.commands
+            .iter()
+            .filter(|cmd| !cmd.is_hidden())
+            .map(|cmd| cmd.name.clone())
+            .filter(|name| name != "cli")
+            .chain(
+                self.has_completions_command()
+                    .then(|| "completions".to_owned()),
+            )
+            .chain(std::iter::once("help".to_owned()))
+            .collect()
+    }
     pub fn add_command(
         &mut self,
         command_name: impl Into<String>,
         description: Option<impl Into<String>>,
     ) -> &mut CliCommand {
-        let command = CliCommand::new(command_name, description);
+        let mut command = CliCommand::new(command_name, description);
+        command.set_placeholder_style(self.placeholder_style);
         self.commands.push(command);
         self.commands.last_mut().unwrap()
     }
@@ -1,4 +1,4 @@
-use super::{CliArgument, CliOption};
+use super::{CliArgument, CliOption, HelpText, PlaceholderStyle};
 
 #[derive(Debug, Clone)]
 pub struct CliCommand {
@@ -6,6 +6,16 @@ pub struct CliCommand {
     pub(crate) description: Option<String>,
     pub(crate) options: Vec<CliOption>,
     pub(crate) arguments: Vec<CliArgument>,
+    pub(crate) section: Option<String>,
+    pub(crate) help_text: HelpText,
+    // Left out of the help and the suggestions, but still usable
+    pub(crate) hidden: bool,
+    // Printed as a warning when the command is used, also marks it in the help
+    pub(crate) deprecated: Option<String>,
+    // The command that runs in place of this one, which is then only an old name for it
+    pub(crate) replaced_by: Option<String>,
+    // Handed down to the arguments as they are added
+    pub(crate) placeholder_style: PlaceholderStyle,
 }
 
 impl CliCommand {
@@ -15,14 +25,77 @@ impl CliCommand {
             description: description.map(|d| d.into()),
             options: Vec::new(),
             arguments: Vec::new(),
+            section: None,
+            help_text: HelpText::default(),
+            hidden: false,
+            deprecated: None,
+            replaced_by: None,
+            placeholder_style: PlaceholderStyle::default(),
         }
     }
-    pub fn add_argument(&mut self, argument: CliArgument) -> &mut Self {
+    pub fn set_long_about(&mut self, long_about: Option<impl Into<String>>) -> &mut Self {
+        self.help_text.long_about = long_about.map(|l| l.into());
+        self
+    }
+    pub fn set_before_help(&mut self, before_help: Option<impl Into<String>>) -> &mut Self {
+        self.help_text.before_help = before_help.map(|b| b.into());
+        self
+    }
+    pub fn set_after_help(&mut self, after_help: Option<impl Into<String>>) -> &mut Self {
+        self.help_text.after_help = after_help.map(|a| a.into());
+        self
+    }
+    pub fn add_example(
+        &mut self,
+        command: impl Into<String>,
+        description: impl Into<String>,
+    ) -> &mut Self {
+        self.help_text
+            .examples
+            .push((command.into(), description.into()));
+        self
+    }
+    // Heading the command is listed under in the help
+    pub fn set_section(&mut self, section: Option<impl Into<String>>) -> &mut Self {
+        self.section = section.map(|s| s.into());
+        self
+    }
+    pub fn set_hidden(&mut self, hidden: bool) -> &mut Self {
+        self.hidden = hidden;
+        self
+    }
+    // e.g. `use push instead`
+    pub fn set_deprecated(&mut self, note: Option<impl Into<String>>) -> &mut Self {
+        self.deprecated = note.map(|n| n.into());
+        self
+    }
+    // Forwards the command to another one, e.g. an old name to the renamed command
+    pub fn set_replaced_by(&mut self, command: Option<impl Into<String>>) -> &mut Self {
+        self.replaced_by = command.map(|c| c.into());
+        self
+    }
+
+    pub fn add_argument(&mut self, mut argument: CliArgument) -> &mut Self {
+        argument.set_placeholder_style(self.placeholder_style);
         self.arguments.push(argument);
         self
     }
-    pub fn add_option(&mut self, option: CliOption) -> &mut Self {
+    pub fn add_option(&mut self, mut option: CliOption) -> &mut Self {
+        option.set_placeholder_style(self.placeholder_style);
         self.options.push(option);
         self
     }
+    // Hidden commands and old names of replaced ones aren't listed anywhere
+    pub(crate) fn is_hidden(&self) -> bool {
+        self.hidden || self.replaced_by.is_some()
+    }
+    pub(crate) fn set_placeholder_style(&mut self, style: PlaceholderStyle) {
+        self.placeholder_style = style;
+        for arg in &mut self.arguments {
+            arg.set_placeholder_style(style);
+        }
+        for opt in &mut self.options {
+            opt.set_placeholder_style(style);
+        }
+    }
 }
@@ -0,0 +1,12 @@
+// Extra help text shared by the top-level page (`Cli`) and every `CliCommand`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HelpText {
+    // Replaces the description in the long help (`--help` / `help <command>`)
+    pub(crate) long_about: Option<String>,
+    // Printed above the usage line
+    pub(crate) before_help: Option<String>,
+    // Printed below every section
+    pub(crate) after_help: Option<String>,
+    // (command line, description), only shown in the long help
+    pub(crate) examples: Vec<(String, String)>,
+}
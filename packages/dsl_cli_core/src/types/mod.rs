@@ -1,9 +1,17 @@
 mod argument;
+mod autocorrect;
 mod cli;
 mod command;
+mod help_text;
 mod option;
+mod placeholder;
+mod value_hint;
 
 pub use argument::CliArgument;
+pub use autocorrect::Autocorrect;
 pub use cli::Cli;
 pub use command::CliCommand;
+pub(crate) use help_text::HelpText;
 pub use option::{CliOption, CliOptionFlags};
+pub use placeholder::PlaceholderStyle;
+pub use value_hint::ValueHint;
@@ -1,4 +1,4 @@
-use super::CliArgument;
+use super::{CliArgument, PlaceholderStyle};
 
 #[derive(Debug, Clone)]
 pub enum CliOptionFlags {
@@ -44,6 +44,15 @@ pub struct CliOption {
     pub(crate) description: Option<String>,
     pub(crate) optional: bool,
     pub(crate) args: Vec<CliArgument>,
+    // Environment variable used when the option isn't given on the command line
+    pub(crate) env: Option<String>,
+    pub(crate) section: Option<String>,
+    // Left out of the help and the suggestions, but still usable
+    pub(crate) hidden: bool,
+    // Printed as a warning when the option is used, also marks it in the help
+    pub(crate) deprecated: Option<String>,
+    // Name of the option of the same command that receives the values instead
+    pub(crate) replaced_by: Option<String>,
 }
 
 impl CliOption {
@@ -59,10 +68,54 @@ impl CliOption {
             description: description.map(|d| d.into()),
             optional,
             args: Vec::new(),
+            env: None,
+            section: None,
+            hidden: false,
+            deprecated: None,
+            replaced_by: None,
         }
     }
     pub fn add_argument(&mut self, argument: CliArgument) -> &mut Self {
         self.args.push(argument);
         self
     }
+    pub fn set_env(&mut self, env: Option<impl Into<String>>) -> &mut Self {
+        self.env = env.map(|e| e.into());
+        self
+    }
+    // Heading the option is listed under in the help
+    pub fn set_section(&mut self, section: Option<impl Into<String>>) -> &mut Self {
+        self.section = section.map(|s| s.into());
+        self
+    }
+    pub fn set_hidden(&mut self, hidden: bool) -> &mut Self {
+        self.hidden = hidden;
+        self
+    }
+    // e.g. `use --output instead`
+    pub fn set_deprecated(&mut self, note: Option<impl Into<String>>) -> &mut Self {
+        self.deprecated = note.map(|n| n.into());
+        self
+    }
+    // Makes the option an old spelling of another one, which takes the arguments and values
+    pub fn set_replaced_by(&mut self, option_name: Option<impl Into<String>>) -> &mut Self {
+        self.replaced_by = option_name.map(|o| o.into());
+        self
+    }
+    // Hidden options and old spellings of replaced ones aren't listed anywhere
+    pub(crate) fn is_hidden(&self) -> bool {
+        self.hidden || self.replaced_by.is_some()
+    }
+    pub(crate) fn set_placeholder_style(&mut self, style: PlaceholderStyle) {
+        for arg in &mut self.args {
+            arg.set_placeholder_style(style);
+        }
+    }
+    // The flag to show in usage lines, the long one when available
+    pub(crate) fn primary_flag(&self) -> String {
+        match &self.flags {
+            CliOptionFlags::Short(c) => format!("-{}", c),
+            CliOptionFlags::Long(s) | CliOptionFlags::ShortAndLong(_, s) => format!("--{}", s),
+        }
+    }
 }
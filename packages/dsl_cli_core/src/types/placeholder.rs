@@ -0,0 +1,89 @@
+// How value placeholders are written when an argument has no `value_name`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlaceholderStyle {
+    // `<file>`
+    #[default]
+    Lowercase,
+    // `<FILE>`
+    Uppercase,
+    // `<PathBuf>`, falls back to the argument name when the type isn't known
+    TypeName,
+}
+
+impl PlaceholderStyle {
+    pub(crate) fn apply(&self, name: &str, type_name: Option<&str>, position: usize) -> String {
+        match self {
+            PlaceholderStyle::Lowercase => name.to_lowercase(),
+            PlaceholderStyle::Uppercase => name.to_uppercase(),
+            PlaceholderStyle::TypeName => type_name
+                .map(|ty| value_type_name(ty, position))
+                .unwrap_or_else(|| name.to_owned()),
+        }
+    }
+}
+
+// The type of a single value: `Option<Vec<u32>>` -> `u32`, `(String, u32)` at 1 -> `u32`,
+// `[u8; 4]` -> `u8`, `HashMap<String, u32>` -> `String=u32`
+pub(crate) fn value_type_name(type_name: &str, position: usize) -> String {
+    let mut ty = type_name.trim();
+    while let Some(inner) = strip_generic(ty, "Option").or_else(|| strip_generic(ty, "Vec")) {
+        ty = inner;
+    }
+
+    if let Some(inner) = strip_generic(ty, "HashMap").or_else(|| strip_generic(ty, "BTreeMap")) {
+        return split_top_level(inner).join("=");
+    }
+    if let Some(inner) = ty.strip_prefix('[').and_then(|ty| ty.strip_suffix(']')) {
+        return inner.split(';').next().unwrap_or(inner).trim().to_owned();
+    }
+    if let Some(inner) = ty.strip_prefix('(').and_then(|ty| ty.strip_suffix(')')) {
+        let elements = split_top_level(inner);
+        return elements
+            .get(position)
+            .or(elements.last())
+            .cloned()
+            .unwrap_or_default();
+    }
+
+    // `std::path::PathBuf` -> `PathBuf`
+    match ty.split_once('<') {
+        Some((path, generics)) => {
+            format!("{}<{}", path.rsplit("::").next().unwrap_or(path), generics)
+        }
+        None => ty.rsplit("::").next().unwrap_or(ty).to_owned(),
+    }
+}
+
+// `Vec<u32>` -> `u32` for the wrapper `Vec`, also for paths like `std::vec::Vec<u32>`
+fn strip_generic<'a>(ty: &'a str, wrapper: &str) -> Option<&'a str> {
+    let (path, rest) = ty.split_once('<')?;
+    if path.rsplit("::").next()? != wrapper {
+        return None;
+    }
+    rest.strip_suffix('>')
+}
+
+// Splits on commas that aren't nested in `<>`, `()` or `[]`
+fn split_top_level(text: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut current = String::new();
+
+    for c in text.chars() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(std::mem::take(&mut current).trim().to_owned());
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        parts.push(current.trim().to_owned());
+    }
+
+    parts
+}
@@ -0,0 +1,11 @@
+// What kind of value an argument takes, used by the shell completion scripts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ValueHint {
+    // Any text, nothing is completed
+    #[default]
+    Text,
+    // File paths, the default for `PathBuf` arguments
+    File,
+    // Directory paths
+    Dir,
+}
@@ -1,31 +1,67 @@
-use proc_macro2::TokenTree;
+use proc_macro2::{Span, TokenTree};
 use syn::{
-    Expr, Ident, LitStr, Token, Type, braced,
+    Expr, Ident, LitChar, LitInt, LitStr, Token, Type, braced, bracketed,
     parse::{Parse, ParseStream},
     spanned::Spanned,
 };
 
-use crate::utils::is_optional_type;
+use crate::utils::{
+    get_fixed_arity, get_fixed_arity_type, is_map_type, is_optional_type, is_variadic_type,
+    parse_flags,
+};
 
 pub struct CliDsl {
     pub(crate) name: LitStr,
     pub(crate) version: LitStr,
     pub(crate) description: LitStr,
+    pub(crate) help_text: HelpText,
     pub(crate) commands: Vec<Command>,
+    // Adds the built-in `completions <shell>` command
+    pub(crate) completions: bool,
+    // Old names forwarded to other commands, they get no variant in the generated enum
+    pub(crate) replaced_commands: Vec<Command>,
+}
+
+// long_about, before_help, after_help and examples, allowed for the cli and for every command
+#[derive(Default)]
+pub struct HelpText {
+    pub(crate) long_about: Option<LitStr>,
+    pub(crate) before_help: Option<LitStr>,
+    pub(crate) after_help: Option<LitStr>,
+    pub(crate) examples: Vec<(LitStr, LitStr)>,
 }
 
 pub struct Command {
     pub(crate) name: Ident,
     pub(crate) description: Option<LitStr>,
+    // Category in the top-level command list
+    pub(crate) section: Option<LitStr>,
+    pub(crate) help_text: HelpText,
     pub(crate) arguments: Vec<Argument>,
     pub(crate) options: Vec<CliOption>,
+    // Old spellings forwarded to other options, they get no field in the generated struct
+    pub(crate) replaced_options: Vec<CliOption>,
+    pub(crate) hidden: bool,
+    pub(crate) deprecated: Option<LitStr>,
+    pub(crate) replaced_by: Option<Ident>,
 }
 
 pub struct Argument {
     pub(crate) name: Ident,
     pub(crate) description: Option<LitStr>,
     pub(crate) ty: Type,
+    pub(crate) delimiter: Option<LitChar>,
+    pub(crate) min: Option<usize>,
+    pub(crate) max: Option<usize>,
+    pub(crate) choices: Vec<LitStr>,
+    pub(crate) value_name: Option<LitStr>,
+    // file, dir or text, what the completion scripts offer for the value
+    pub(crate) value_hint: Option<Ident>,
     pub(crate) default: Option<Expr>,
+    // Help heading, only for positional arguments
+    pub(crate) section: Option<LitStr>,
+    pub(crate) hidden: bool,
+    pub(crate) deprecated: Option<LitStr>,
 }
 
 pub struct CliOption {
@@ -33,6 +69,13 @@ pub struct CliOption {
     pub(crate) description: Option<LitStr>,
     pub(crate) arguments: Vec<Argument>,
     pub(crate) required: bool,
+    pub(crate) env: Option<LitStr>,
+    // Help heading
+    pub(crate) section: Option<LitStr>,
+    pub(crate) hidden: bool,
+    pub(crate) deprecated: Option<LitStr>,
+    // Field name of the option that takes the values
+    pub(crate) replaced_by: Option<Ident>,
 }
 
 // ----------------------------------------------------------------
@@ -56,24 +99,79 @@ impl Parse for CliDsl {
         let description: LitStr = input.parse()?;
         input.parse::<Token![,]>()?;
 
-        // Parse commands
+        // Parse help text for the top-level page, and the optional `completions,`
+        let mut help_text = HelpText::default();
+        let mut completions = false;
+        while input.peek(Ident) {
+            let keyword: Ident = input.fork().parse()?;
+            if keyword == "completions" {
+                input.parse::<Ident>()?;
+                completions = true;
+                let _ = input.parse::<Token![,]>();
+                continue;
+            }
+            if !is_help_text_keyword(&keyword) {
+                break;
+            }
+            input.parse::<Ident>()?;
+            parse_help_text(input, &keyword, &mut help_text)?;
+        }
+
+        // Parse commands, optionally grouped as: section "<heading>" { cmd ..., }
         let mut commands = Vec::new();
         while !input.is_empty() {
-            commands.push(input.parse()?);
+            if input.peek(Ident) && input.fork().parse::<Ident>()? == "section" {
+                input.parse::<Ident>()?;
+                let section: LitStr = input.parse()?;
+                let content;
+                braced!(content in input);
+                while !content.is_empty() {
+                    let mut command: Command = content.parse()?;
+                    command.section = Some(section.clone());
+                    commands.push(command);
+                }
+                let _ = input.parse::<Token![,]>();
+            } else {
+                commands.push(input.parse()?);
+            }
+        }
+
+        let (replaced_commands, commands): (Vec<Command>, Vec<Command>) = commands
+            .into_iter()
+            .partition(|cmd| cmd.replaced_by.is_some());
+        for replaced in &replaced_commands {
+            let replaced_by = replaced.replaced_by.as_ref().unwrap();
+            if !commands.iter().any(|cmd| cmd.name == *replaced_by) {
+                return Err(syn::Error::new(
+                    replaced_by.span(),
+                    format!("Unknown command '{}' in 'replaced_by'.", replaced_by),
+                ));
+            }
+        }
+
+        if completions && let Some(cmd) = commands.iter().find(|cmd| cmd.name == "completions") {
+            return Err(syn::Error::new(
+                cmd.name.span(),
+                "A command named 'completions' conflicts with the built-in one, remove 'completions,' from the top of the cli to define your own.",
+            ));
         }
 
         Ok(CliDsl {
             name,
             version,
             description,
+            help_text,
             commands,
+            completions,
+            replaced_commands,
         })
     }
 }
 
 impl Parse for Command {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        // Parse: cmd <name> ["description"] { ... }
+        // Parse: cmd <name> ["description"] [hidden] [deprecated "<note>"] { ... }
+        //    or: cmd <name> ["description"] deprecated "<note>" replaced_by <command>,
         let cmd_keyword: Ident = input.parse()?;
         if cmd_keyword != "cmd" {
             return Err(syn::Error::new(cmd_keyword.span(), "expected 'cmd'"));
@@ -88,31 +186,46 @@ impl Parse for Command {
             None
         };
 
-        // Parse body
-        let content;
-        braced!(content in input);
+        let (hidden, deprecated, replaced_by) = parse_markers(input)?;
+        if name == "cli" && (hidden || deprecated.is_some()) {
+            return Err(syn::Error::new(
+                name.span(),
+                "The 'cli' command can't be hidden or deprecated.",
+            ));
+        }
 
         let mut arguments = Vec::new();
         let mut options = Vec::new();
+        let mut help_text = HelpText::default();
 
-        while !content.is_empty() {
-            let keyword: Ident = content.parse()?;
-            match keyword.to_string().as_str() {
-                "arg" => {
-                    arguments.push(parse_argument(&content, true, true)?);
-                }
-                "opt" => {
-                    options.push(parse_option(&content, false)?);
-                }
-                "req_opt" => {
-                    options.push(parse_option(&content, true)?);
-                }
-                _ => {
-                    return Err(syn::Error::new(
-                        keyword.span(),
-                        format!("unexpected keyword '{}'", keyword),
-                    ));
-                }
+        // A replaced command runs its replacement, so it has nothing of its own to parse
+        if replaced_by.is_none() {
+            let content;
+            braced!(content in input);
+
+            parse_command_items(&content, &mut arguments, &mut options, &mut help_text, None)?;
+
+            check_positional_layout(&arguments)?;
+        } else if input.peek(syn::token::Brace) {
+            return Err(syn::Error::new(
+                input.span(),
+                "A command with 'replaced_by' takes the arguments and options of its replacement, it can't have a body.",
+            ));
+        }
+
+        let (replaced_options, options): (Vec<CliOption>, Vec<CliOption>) = options
+            .into_iter()
+            .partition(|opt| opt.replaced_by.is_some());
+        for replaced in &replaced_options {
+            let replaced_by = replaced.replaced_by.as_ref().unwrap();
+            if !options
+                .iter()
+                .any(|opt| *replaced_by == parse_flags(&opt.flags.value()).2)
+            {
+                return Err(syn::Error::new(
+                    replaced_by.span(),
+                    format!("Unknown option '{}' in 'replaced_by'.", replaced_by),
+                ));
             }
         }
 
@@ -122,18 +235,185 @@ impl Parse for Command {
         Ok(Command {
             name,
             description,
+            section: None,
+            help_text,
             arguments,
             options,
+            replaced_options,
+            hidden,
+            deprecated,
+            replaced_by,
         })
     }
 }
 
+// Parses the arguments and options of a command body. A `section "<heading>" { ... }` block
+// groups its items under a heading in the help, it doesn't change how they are parsed.
+fn parse_command_items(
+    content: ParseStream,
+    arguments: &mut Vec<Argument>,
+    options: &mut Vec<CliOption>,
+    help_text: &mut HelpText,
+    section: Option<&LitStr>,
+) -> syn::Result<()> {
+    while !content.is_empty() {
+        let keyword: Ident = content.parse()?;
+        match keyword.to_string().as_str() {
+            "arg" => {
+                let mut argument = parse_argument(content, true, true)?;
+                argument.section = section.cloned();
+                arguments.push(argument);
+            }
+            "opt" | "req_opt" => {
+                let mut option = parse_option(content, keyword == "req_opt")?;
+                option.section = section.cloned();
+                options.push(option);
+            }
+            "section" if section.is_none() => {
+                let heading: LitStr = content.parse()?;
+                let inner;
+                braced!(inner in content);
+                parse_command_items(&inner, arguments, options, help_text, Some(&heading))?;
+                let _ = content.parse::<Token![,]>();
+            }
+            _ if is_help_text_keyword(&keyword) => {
+                parse_help_text(content, &keyword, help_text)?;
+            }
+            "section" => {
+                return Err(syn::Error::new(keyword.span(), "Sections can't be nested."));
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    keyword.span(),
+                    format!("unexpected keyword '{}'", keyword),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+// Parses the optional `hidden`, `deprecated "<note>"` and `replaced_by <name>` clauses of a
+// command or an option. A replacement only makes sense for something that is deprecated.
+fn parse_markers(input: ParseStream) -> syn::Result<(bool, Option<LitStr>, Option<Ident>)> {
+    let mut hidden = false;
+    let mut deprecated = None;
+    let mut replaced_by: Option<Ident> = None;
+    while input.peek(Ident) {
+        let keyword: Ident = input.fork().parse()?;
+        match keyword.to_string().as_str() {
+            "hidden" => {
+                input.parse::<Ident>()?;
+                hidden = true;
+            }
+            "deprecated" => {
+                input.parse::<Ident>()?;
+                deprecated = Some(input.parse()?);
+            }
+            "replaced_by" => {
+                input.parse::<Ident>()?;
+                replaced_by = Some(input.parse()?);
+            }
+            _ => break,
+        }
+    }
+
+    if let Some(replaced_by) = &replaced_by
+        && deprecated.is_none()
+    {
+        return Err(syn::Error::new(
+            replaced_by.span(),
+            "'replaced_by' needs a 'deprecated' note.",
+        ));
+    }
+    Ok((hidden, deprecated, replaced_by))
+}
+
+fn is_help_text_keyword(keyword: &Ident) -> bool {
+    ["long_about", "before_help", "after_help", "examples"]
+        .iter()
+        .any(|k| keyword == k)
+}
+
+// Parses what follows a help text keyword:
+// long_about|before_help|after_help "<text>",
+// examples { "<command line>" => "<description>", ... },
+fn parse_help_text(
+    input: ParseStream,
+    keyword: &Ident,
+    help_text: &mut HelpText,
+) -> syn::Result<()> {
+    let slot = match keyword.to_string().as_str() {
+        "long_about" => &mut help_text.long_about,
+        "before_help" => &mut help_text.before_help,
+        "after_help" => &mut help_text.after_help,
+        _ => {
+            let content;
+            braced!(content in input);
+            while !content.is_empty() {
+                let command: LitStr = content.parse()?;
+                content.parse::<Token![=>]>()?;
+                let description: LitStr = content.parse()?;
+                help_text.examples.push((command, description));
+                let _ = content.parse::<Token![,]>();
+            }
+            let _ = input.parse::<Token![,]>();
+            return Ok(());
+        }
+    };
+
+    if slot.is_some() {
+        return Err(syn::Error::new(
+            keyword.span(),
+            format!("'{}' is already set.", keyword),
+        ));
+    }
+    *slot = Some(input.parse()?);
+    let _ = input.parse::<Token![,]>();
+    Ok(())
+}
+
+// Positional values are assigned by count, so the layout must leave no doubt about which
+// argument a value belongs to: at most one variadic, and no optional arguments after it, as
+// `<files...> [dst]` could read the last value either way.
+fn check_positional_layout(arguments: &[Argument]) -> syn::Result<()> {
+    let takes_many = |arg: &Argument| is_variadic_type(&arg.ty) && arg.delimiter.is_none();
+
+    let mut variadics = arguments.iter().filter(|arg| takes_many(arg));
+    let Some(variadic) = variadics.next() else {
+        return Ok(());
+    };
+    if let Some(other) = variadics.next() {
+        return Err(syn::Error::new(
+            other.name.span(),
+            format!(
+                "Ambiguous arguments: '{}' and '{}' are both variadic, only one variadic argument is allowed per command.",
+                variadic.name, other.name
+            ),
+        ));
+    }
+    if let Some(optional) = arguments
+        .iter()
+        .skip_while(|arg| !takes_many(arg))
+        .find(|arg| !takes_many(arg) && is_optional_type(&arg.ty))
+    {
+        return Err(syn::Error::new(
+            optional.name.span(),
+            format!(
+                "Ambiguous arguments: optional argument '{}' can't come after variadic argument '{}', move it before the variadic one.",
+                optional.name, variadic.name
+            ),
+        ));
+    }
+    Ok(())
+}
+
 fn parse_argument(
     input: ParseStream,
     is_positional: bool,
     is_ctx_required: bool,
 ) -> syn::Result<Argument> {
-    // arg <name> ["description"] [: type] [= <default>],
+    // arg <name> ["description"] [: type] [delimiter '<char>'] [min <n>] [max <n>] [choices ["<value>", ...]] [value_name "<NAME>"] [value_hint file|dir|text] [hidden] [deprecated "<note>"] [= <default>],
     let name: Ident = input.parse()?;
 
     // Optional description
@@ -150,12 +430,15 @@ fn parse_argument(
     };
 
     // Parse type (default is String)
-    // Parse type (default is String)
-    let ty: Type = if input.is_empty() || input.peek(Token![,]) || input.peek(Token![=]) {
-        syn::parse_quote!(String)
-    } else if input.peek(Token![:]) {
+    let ty: Type = if input.peek(Token![:]) {
         input.parse::<Token![:]>()?;
         input.parse()?
+    } else if input.is_empty()
+        || input.peek(Token![,])
+        || input.peek(Token![=])
+        || input.peek(Ident)
+    {
+        syn::parse_quote!(String)
     } else {
         let unexpected: TokenTree = input.parse()?;
         return Err(syn::Error::new(
@@ -165,10 +448,170 @@ fn parse_argument(
     };
     let ty_is_option = is_optional_type(&ty);
 
+    if get_fixed_arity_type(&ty).is_some() {
+        if is_positional {
+            return Err(syn::Error::new(
+                ty.span(),
+                "Tuple and array types are only allowed for option arguments.",
+            ));
+        }
+        if get_fixed_arity(&ty).is_none() {
+            return Err(syn::Error::new(
+                ty.span(),
+                "The length of an array argument must be an integer literal.",
+            ));
+        }
+    }
+
+    if is_map_type(&ty) && is_positional {
+        return Err(syn::Error::new(
+            ty.span(),
+            "Map types (*HashMap<K, V>* / *BTreeMap<K, V>*) are only allowed for option arguments.",
+        ));
+    }
+
+    // Optional clauses, in any order
+    let mut delimiter: Option<LitChar> = None;
+    // The parsed count, and where it was written for errors
+    let mut min: Option<(usize, Span)> = None;
+    let mut max: Option<(usize, Span)> = None;
+    let mut choices: Vec<LitStr> = Vec::new();
+    let mut value_name: Option<LitStr> = None;
+    let mut value_hint: Option<Ident> = None;
+    let mut hidden = false;
+    let mut deprecated: Option<LitStr> = None;
+    while input.peek(Ident) {
+        let keyword: Ident = input.fork().parse()?;
+        match keyword.to_string().as_str() {
+            // Splits a single token into multiple values
+            "delimiter" => {
+                input.parse::<Ident>()?;
+                if !is_variadic_type(&ty) && !is_map_type(&ty) {
+                    return Err(syn::Error::new(
+                        keyword.span(),
+                        "A delimiter is only allowed for *Vec<T>* or map types.",
+                    ));
+                }
+                delimiter = Some(input.parse()?);
+            }
+            // Placeholder shown in help and errors instead of the name
+            "value_name" => {
+                input.parse::<Ident>()?;
+                let name: LitStr = input.parse()?;
+                if name.value().trim().is_empty() {
+                    return Err(syn::Error::new(name.span(), "'value_name' can't be empty."));
+                }
+                value_name = Some(name);
+            }
+            // Overrides the completion derived from the type
+            "value_hint" => {
+                input.parse::<Ident>()?;
+                let hint: Ident = input.parse()?;
+                if !["file", "dir", "text"].iter().any(|h| hint == h) {
+                    return Err(syn::Error::new(
+                        hint.span(),
+                        "'value_hint' must be one of: file, dir, text.",
+                    ));
+                }
+                value_hint = Some(hint);
+            }
+            // Bounds on the number of values of a variadic argument
+            "min" | "max" => {
+                input.parse::<Ident>()?;
+                if !is_variadic_type(&ty) {
+                    return Err(syn::Error::new(
+                        keyword.span(),
+                        format!("'{}' is only allowed for *Vec<T>* types.", keyword),
+                    ));
+                }
+                let slot = if keyword == "min" { &mut min } else { &mut max };
+                if slot.is_some() {
+                    return Err(syn::Error::new(
+                        keyword.span(),
+                        format!("'{}' is already set.", keyword),
+                    ));
+                }
+                let count: LitInt = input.parse()?;
+                *slot = Some((count.base10_parse::<usize>()?, count.span()));
+            }
+            // Restricts the accepted values
+            "choices" => {
+                input.parse::<Ident>()?;
+                if is_map_type(&ty) {
+                    return Err(syn::Error::new(
+                        keyword.span(),
+                        "'choices' is not supported for map types.",
+                    ));
+                }
+                let content;
+                bracketed!(content in input);
+                choices = content
+                    .parse_terminated(|input| input.parse::<LitStr>(), Token![,])?
+                    .into_iter()
+                    .collect();
+                if choices.is_empty() {
+                    return Err(syn::Error::new(
+                        keyword.span(),
+                        "'choices' needs at least one value.",
+                    ));
+                }
+            }
+            // Left out of the help
+            "hidden" => {
+                input.parse::<Ident>()?;
+                if !is_positional {
+                    return Err(syn::Error::new(
+                        keyword.span(),
+                        "Only positional arguments can be hidden, hide the option instead.",
+                    ));
+                }
+                hidden = true;
+            }
+            // Warns when a value is given
+            "deprecated" => {
+                input.parse::<Ident>()?;
+                if !is_positional {
+                    return Err(syn::Error::new(
+                        keyword.span(),
+                        "Only positional arguments can be deprecated, deprecate the option instead.",
+                    ));
+                }
+                deprecated = Some(input.parse()?);
+            }
+            _ => break,
+        }
+    }
+
+    if let (Some((min, _)), Some((max, max_span))) = (min, max)
+        && min > max
+    {
+        return Err(syn::Error::new(
+            max_span,
+            "'max' must be greater than or equal to 'min'.",
+        ));
+    }
+    if let Some((0, max_span)) = max {
+        return Err(syn::Error::new(max_span, "'max' must be at least 1."));
+    }
+    // The parser needs at least one value for a variadic argument that can't be left out
+    if let Some((0, min_span)) = min {
+        return Err(syn::Error::new(
+            min_span,
+            "'min' must be at least 1, use *Option<Vec<T>>* for an argument that can be left out.",
+        ));
+    }
+
     // Optional default value
     let default = if input.peek(Token![=]) {
         let asignment = input.parse::<Token![=]>()?;
 
+        if is_map_type(&ty) || get_fixed_arity_type(&ty).is_some() {
+            return Err(syn::Error::new(
+                asignment.span(),
+                "Default values are not supported for map, tuple or array types.",
+            ));
+        }
+
         if !ty_is_option && is_ctx_required {
             return Err(syn::Error::new(
                 asignment.span(),
@@ -188,12 +631,22 @@ fn parse_argument(
         name,
         description,
         ty,
+        delimiter,
+        min: min.map(|(min, _)| min),
+        max: max.map(|(max, _)| max),
+        choices,
+        value_name,
+        value_hint,
         default,
+        section: None,
+        hidden,
+        deprecated,
     })
 }
 
 fn parse_option(input: ParseStream, required: bool) -> syn::Result<CliOption> {
-    // opt|req_opt "<flags>" ["description"] [{args}],
+    // opt|req_opt "<flags>" ["description"] [env "<VAR>"] [hidden] [deprecated "<note>"] [{args}],
+    //    or: opt "<flags>" ["description"] deprecated "<note>" replaced_by <option>,
     let flags: LitStr = input.parse()?;
 
     // Optional description
@@ -203,6 +656,38 @@ fn parse_option(input: ParseStream, required: bool) -> syn::Result<CliOption> {
         None
     };
 
+    // Optional environment variable
+    let env: Option<LitStr> = if input.peek(Ident) && input.fork().parse::<Ident>()? == "env" {
+        input.parse::<Ident>()?;
+        Some(input.parse()?)
+    } else {
+        None
+    };
+
+    let (hidden, deprecated, replaced_by) = parse_markers(input)?;
+    if input.peek(Ident) {
+        let keyword: Ident = input.parse()?;
+        return Err(syn::Error::new(
+            keyword.span(),
+            "expected 'env', 'hidden', 'deprecated', 'replaced_by' or '{'",
+        ));
+    }
+    // The replacement parses the values, the old spelling is only another way to write its flag
+    if let Some(replaced_by) = &replaced_by {
+        let conflict = if required {
+            Some("'replaced_by' is not allowed for required options.")
+        } else if env.is_some() {
+            Some("'env' is not allowed together with 'replaced_by'.")
+        } else if input.peek(syn::token::Brace) {
+            Some("An option with 'replaced_by' takes the arguments of its replacement.")
+        } else {
+            None
+        };
+        if let Some(message) = conflict {
+            return Err(syn::Error::new(replaced_by.span(), message));
+        }
+    }
+
     // Optional arguments block
     let arguments = if input.peek(syn::token::Brace) {
         let content;
@@ -219,6 +704,14 @@ fn parse_option(input: ParseStream, required: bool) -> syn::Result<CliOption> {
                 ));
             }
         }
+        if args.len() > 1
+            && let Some(map_arg) = args.iter().find(|arg| is_map_type(&arg.ty))
+        {
+            return Err(syn::Error::new(
+                map_arg.name.span(),
+                "A map argument must be the only argument of its option.",
+            ));
+        }
         args
     } else {
         Vec::new()
@@ -232,5 +725,34 @@ fn parse_option(input: ParseStream, required: bool) -> syn::Result<CliOption> {
         description,
         arguments,
         required,
+        env,
+        section: None,
+        hidden,
+        deprecated,
+        replaced_by,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::parse::{ParseStream, Parser};
+
+    use super::parse_argument;
+
+    fn argument_error(tokens: proc_macro2::TokenStream) -> Option<String> {
+        let parser = |input: ParseStream| parse_argument(input, true, true);
+        parser.parse2(tokens).err().map(|e| e.to_string())
+    }
+
+    #[test]
+    fn min_zero_is_rejected() {
+        assert_eq!(
+            argument_error(quote!(files: Vec<String> min 0)).as_deref(),
+            Some(
+                "'min' must be at least 1, use *Option<Vec<T>>* for an argument that can be left out."
+            )
+        );
+        assert_eq!(argument_error(quote!(files: Vec<String> min 1)), None);
+    }
+}
@@ -1,6 +1,10 @@
-use crate::{Argument, CliDsl, CliOption, is_optional_type, is_variadic_type, parse_flags};
-use proc_macro2::TokenStream as TokenStream2;
+use crate::{
+    Argument, CliDsl, CliOption, HelpText, default_display, get_fixed_arity, get_fixed_arity_type,
+    get_map_types, is_map_type, is_optional_type, is_variadic_type, parse_flags, type_name,
+};
+use proc_macro2::{Literal, TokenStream as TokenStream2};
 use quote::quote;
+use syn::Type;
 
 pub fn generate_arg_def(arg: &Argument) -> TokenStream2 {
     let arg_name = arg.name.to_string();
@@ -10,14 +14,116 @@ pub fn generate_arg_def(arg: &Argument) -> TokenStream2 {
     };
     let optional = is_optional_type(&arg.ty);
     let variadic = is_variadic_type(&arg.ty);
+    let map = is_map_type(&arg.ty);
+    // Entries are parsed into the key and value types while parsing, not in `from_parsed`
+    let map_entry_check = match get_map_types(&arg.ty) {
+        Some((key, value)) => quote! {
+            Some((|key: &str, value: &str| {
+                key.parse::<#key>().is_ok() && value.parse::<#value>().is_ok()
+            }) as fn(&str, &str) -> bool)
+        },
+        None => quote! { None },
+    };
+    // Tuple and array values are parsed into their element types while parsing as well
+    let element_check = match get_fixed_arity_type(&arg.ty) {
+        Some(Type::Tuple(tuple)) => {
+            let positions = tuple.elems.iter().enumerate().map(|(position, elem_ty)| {
+                quote! { #position => value.parse::<#elem_ty>().is_ok() }
+            });
+            quote! {
+                Some((|position: usize, value: &str| match position {
+                    #(#positions,)*
+                    _ => true,
+                }) as fn(usize, &str) -> bool)
+            }
+        }
+        Some(Type::Array(array)) => {
+            let elem_ty = &array.elem;
+            quote! {
+                Some((|_: usize, value: &str| value.parse::<#elem_ty>().is_ok())
+                    as fn(usize, &str) -> bool)
+            }
+        }
+        _ => quote! { None },
+    };
+    let arity = match get_fixed_arity(&arg.ty) {
+        Some(arity) => quote! { Some(#arity) },
+        None => quote! { None },
+    };
+    // Unsuffixed, so `min 2u8` still fits the `usize` setter
+    let min_values = match arg.min.map(Literal::usize_unsuffixed) {
+        Some(min) => quote! { Some(#min) },
+        None => quote! { None },
+    };
+    let max_values = match arg.max.map(Literal::usize_unsuffixed) {
+        Some(max) => quote! { Some(#max) },
+        None => quote! { None },
+    };
+    let delimiter = match &arg.delimiter {
+        Some(d) => quote! { Some(#d) },
+        None => quote! { None },
+    };
+    let type_name = type_name(&arg.ty);
+    let default = match &arg.default {
+        Some(default) => {
+            let default = default_display(default);
+            quote! { Some(#default) }
+        }
+        None => quote! { None::<&str> },
+    };
+    let choices = &arg.choices;
+    let value_name = match &arg.value_name {
+        Some(value_name) => quote! { Some(#value_name) },
+        None => quote! { None::<&str> },
+    };
+    let section = match &arg.section {
+        Some(section) => quote! { Some(#section) },
+        None => quote! { None::<&str> },
+    };
+    let value_hint = match arg.value_hint.as_ref().map(|hint| hint.to_string()) {
+        Some(hint) => {
+            let variant = match hint.as_str() {
+                "file" => quote! { File },
+                "dir" => quote! { Dir },
+                _ => quote! { Text },
+            };
+            quote! { Some(dsl_cli::dsl_cli_core::ValueHint::#variant) }
+        }
+        None => quote! { None },
+    };
+    let hidden = arg.hidden;
+    let deprecated = match &arg.deprecated {
+        Some(note) => quote! { Some(#note) },
+        None => quote! { None::<&str> },
+    };
 
     quote! {
-        dsl_cli::dsl_cli_core::CliArgument::new(
-            #arg_name.to_string(),
-            #arg_desc,
-            #optional,
-            #variadic,
-        )
+        {
+            let mut __arg = dsl_cli::dsl_cli_core::CliArgument::new(
+                #arg_name.to_string(),
+                #arg_desc,
+                #optional,
+                #variadic,
+            );
+
+            __arg.set_map(#map);
+            __arg.set_map_entry_check(#map_entry_check);
+            __arg.set_delimiter(#delimiter);
+            __arg.set_element_check(#element_check);
+            __arg.set_arity(#arity);
+            __arg.set_min_values(#min_values);
+            __arg.set_max_values(#max_values);
+            __arg.set_type_name(#type_name);
+            __arg.set_default(#default);
+            __arg.set_choices(&[#(#choices),*]);
+            __arg.set_section(#section);
+            __arg.set_value_name(#value_name);
+            __arg.set_value_hint(#value_hint);
+            __arg.set_hidden(#hidden);
+            __arg.set_deprecated(#deprecated);
+
+            __arg
+        }
     }
 }
 
@@ -42,6 +148,26 @@ pub fn generate_opt_def(opt: &CliOption) -> TokenStream2 {
     };
 
     let optional = !opt.required;
+    let section = match &opt.section {
+        Some(section) => quote! { Some(#section) },
+        None => quote! { None::<&str> },
+    };
+    let env = match &opt.env {
+        Some(env) => quote! { Some(#env) },
+        None => quote! { None::<&str> },
+    };
+    let hidden = opt.hidden;
+    let deprecated = match &opt.deprecated {
+        Some(note) => quote! { Some(#note) },
+        None => quote! { None::<&str> },
+    };
+    let replaced_by = match &opt.replaced_by {
+        Some(option) => {
+            let option = option.to_string();
+            quote! { Some(#option) }
+        }
+        None => quote! { None::<&str> },
+    };
 
     let opt_arg_defs: Vec<TokenStream2> = opt.arguments.iter().map(generate_arg_def).collect();
 
@@ -54,7 +180,12 @@ pub fn generate_opt_def(opt: &CliOption) -> TokenStream2 {
                 #optional,
             );
 
-            __opt #(.add_argument(#opt_arg_defs))*;
+            __opt.set_env(#env);
+            __opt.set_section(#section);
+            __opt.set_hidden(#hidden);
+            __opt.set_deprecated(#deprecated);
+            __opt.set_replaced_by(#replaced_by);
+            #(__opt.add_argument(#opt_arg_defs);)*
 
             __opt
         }
@@ -66,9 +197,14 @@ pub fn generate_cli_setup(dsl: &CliDsl) -> TokenStream2 {
     let version = &dsl.version;
     let description = &dsl.description;
 
+    let cli_help_text = generate_help_text_setters(&dsl.help_text);
+    let completions = dsl.completions;
+
+    // Replaced commands are registered like the others, with nothing but their replacement
     let command_registrations: Vec<TokenStream2> = dsl
         .commands
         .iter()
+        .chain(&dsl.replaced_commands)
         .map(|cmd| {
             let cmd_name = cmd.name.to_string();
             let cmd_desc = match &cmd.description {
@@ -76,15 +212,44 @@ pub fn generate_cli_setup(dsl: &CliDsl) -> TokenStream2 {
                 None => quote! { None::<&str> },
             };
 
+            let cmd_section = match &cmd.section {
+                Some(section) => quote! { Some(#section) },
+                None => quote! { None::<&str> },
+            };
+
+            let cmd_help_text = generate_help_text_setters(&cmd.help_text);
+
+            let cmd_hidden = cmd.hidden;
+            let cmd_deprecated = match &cmd.deprecated {
+                Some(note) => quote! { Some(#note) },
+                None => quote! { None::<&str> },
+            };
+            let cmd_replaced_by = match &cmd.replaced_by {
+                Some(command) => {
+                    let command = command.to_string();
+                    quote! { Some(#command) }
+                }
+                None => quote! { None::<&str> },
+            };
+
             let arg_defs: Vec<TokenStream2> = cmd.arguments.iter().map(generate_arg_def).collect();
 
-            let opt_registrations: Vec<TokenStream2> =
-                cmd.options.iter().map(generate_opt_def).collect();
+            let opt_registrations: Vec<TokenStream2> = cmd
+                .options
+                .iter()
+                .chain(&cmd.replaced_options)
+                .map(generate_opt_def)
+                .collect();
 
             quote! {
                 {
                     let __cmd = __cli.add_command(#cmd_name, #cmd_desc);
                     __cmd
+                        .set_section(#cmd_section)
+                        .set_hidden(#cmd_hidden)
+                        .set_deprecated(#cmd_deprecated)
+                        .set_replaced_by(#cmd_replaced_by)
+                        #cmd_help_text
                         #(.add_argument(#arg_defs))*
                         #(.add_option(#opt_registrations))*;
                 }
@@ -94,6 +259,27 @@ pub fn generate_cli_setup(dsl: &CliDsl) -> TokenStream2 {
 
     quote! {
         let mut __cli = dsl_cli::dsl_cli_core::Cli::new(#name, #version, #description);
+        __cli #cli_help_text;
+        __cli.set_completions_command(#completions);
         #(#command_registrations)*
     }
 }
+
+// Chained setter calls, shared by the cli and the commands
+fn generate_help_text_setters(help_text: &HelpText) -> TokenStream2 {
+    let optional = |text: &Option<syn::LitStr>| match text {
+        Some(t) => quote! { Some(#t) },
+        None => quote! { None::<&str> },
+    };
+    let long_about = optional(&help_text.long_about);
+    let before_help = optional(&help_text.before_help);
+    let after_help = optional(&help_text.after_help);
+    let (commands, descriptions): (Vec<_>, Vec<_>) = help_text.examples.iter().cloned().unzip();
+
+    quote! {
+        .set_long_about(#long_about)
+        .set_before_help(#before_help)
+        .set_after_help(#after_help)
+        #(.add_example(#commands, #descriptions))*
+    }
+}
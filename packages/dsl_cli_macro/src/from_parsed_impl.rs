@@ -1,10 +1,11 @@
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
-use syn::Ident;
+use syn::{Ident, Type};
 
 use crate::{
-    Command, generate_args_struct_name, get_effective_type, is_optional_type, is_variadic_type,
-    parse_flags, to_pascal_case,
+    Command, generate_args_struct_name, get_effective_type, get_fixed_arity_type,
+    get_option_arg_type, is_map_type, is_optional_type, is_variadic_type, parse_flags,
+    to_pascal_case,
 };
 
 pub fn generate_from_parsed_impl_for_args(cmd: &Command) -> TokenStream2 {
@@ -142,26 +143,48 @@ pub fn generate_from_parsed_impl_for_opts(cmd: &Command) -> TokenStream2 {
             let arg = &opt.arguments[0];
             let is_optional = is_optional_type(&arg.ty);
             let is_variadic = is_variadic_type(&arg.ty);
+            let is_map = is_map_type(&arg.ty);
             let has_default = arg.default.is_some();
 
             // Field type selection:
             // - Defaults always yield a concrete value: unwrap Option<T> when a default exists.
             // - Optional options yield Option<T> unless the arg is already Option<T>.
-            let field_type = if has_default {
-                get_effective_type(arg)
-            } else if opt.required {
-                arg.ty.clone()
-            } else if is_optional {
-                arg.ty.clone()
-            } else {
-                let ty = arg.ty.clone();
-                syn::parse_quote!(Option<#ty>)
-            };
+            let field_type = get_option_arg_type(arg, opt.required);
 
             // If the option itself is optional, the field should be Option<...> (unless default).
             let output_is_option = !has_default && (!opt.required || is_optional);
 
-            if output_is_option {
+            if let Some(fixed_ty) = get_fixed_arity_type(&arg.ty) {
+                field_extractions.push(generate_fixed_arity_extraction(
+                    &field_name,
+                    &field_type,
+                    &opt_name,
+                    fixed_ty,
+                    output_is_option,
+                ));
+            } else if is_map {
+                // HashMap<K, V> / BTreeMap<K, V>, collected from the `KEY=VALUE` entries
+                let missing = if output_is_option {
+                    quote! { None }
+                } else {
+                    quote! { panic!("Missing value for required option '{}'", #opt_name) }
+                };
+                let collected = if output_is_option {
+                    quote! { Some(entries.iter().map(|(k, v)| (k.parse().unwrap(), v.parse().unwrap())).collect()) }
+                } else {
+                    quote! { entries.iter().map(|(k, v)| (k.parse().unwrap(), v.parse().unwrap())).collect() }
+                };
+                field_extractions.push(quote! {
+                    let #field_name: #field_type = {
+                        let val = __parsed.remove(#opt_name).unwrap();
+                        if let Some(entries) = val.downcast_ref::<Vec<(String, String)>>() {
+                            #collected
+                        } else {
+                            #missing
+                        }
+                    };
+                });
+            } else if output_is_option {
                 if is_variadic {
                     field_extractions.push(quote! {
                         let #field_name: #field_type = {
@@ -241,22 +264,20 @@ pub fn generate_from_parsed_impl_for_opts(cmd: &Command) -> TokenStream2 {
                     let is_variadic = is_variadic_type(&arg.ty);
                     let has_default = arg.default.is_some();
 
-           
-                    let arg_field_type = if has_default {
-                        get_effective_type(arg)
-                    } else if opt.required {
-                        arg.ty.clone()
-                    } else if is_optional {
-                        arg.ty.clone()
-                    } else {
-                        let ty = arg.ty.clone();
-                        syn::parse_quote!(Option<#ty>)
-                    };
+                    let arg_field_type = get_option_arg_type(arg, opt.required);
 
                     // When the resulting field type is Option<...>, decode as optional.
                     let output_is_option = !has_default && (!opt.required || is_optional);
 
-                    if has_default {
+                    if let Some(fixed_ty) = get_fixed_arity_type(&arg.ty) {
+                        generate_fixed_arity_extraction(
+                            arg_field_name,
+                            &arg_field_type,
+                            &arg_field_name_str,
+                            fixed_ty,
+                            output_is_option,
+                        )
+                    } else if has_default {
                         let default_val = arg.default.as_ref().unwrap();
                         quote! {
                             let #arg_field_name: #arg_field_type = {
@@ -362,3 +383,45 @@ pub fn generate_from_parsed_impl_for_opts(cmd: &Command) -> TokenStream2 {
         }
     }
 }
+
+// Tuples and arrays are parsed from exactly as many values as they have elements
+fn generate_fixed_arity_extraction(
+    field_name: &Ident,
+    field_type: &Type,
+    key: &str,
+    fixed_ty: &Type,
+    output_is_option: bool,
+) -> TokenStream2 {
+    let conversion = match fixed_ty {
+        Type::Tuple(tuple) => {
+            let elems = tuple.elems.iter().enumerate().map(|(idx, elem_ty)| {
+                quote! { values[#idx].parse::<#elem_ty>().unwrap() }
+            });
+            quote! { (#(#elems,)*) }
+        }
+        _ => quote! { ::std::array::from_fn(|idx| values[idx].parse().unwrap()) },
+    };
+
+    if output_is_option {
+        quote! {
+            let #field_name: #field_type = {
+                let val = __parsed.remove(#key).unwrap();
+                if let Some(values) = val.downcast_ref::<Vec<String>>() {
+                    Some(#conversion)
+                } else {
+                    None
+                }
+            };
+        }
+    } else {
+        quote! {
+            let #field_name: #field_type = {
+                let val = __parsed.remove(#key).unwrap();
+                let values = val
+                    .downcast_ref::<Vec<String>>()
+                    .unwrap_or_else(|| panic!("Missing value for required option '{}'", #key));
+                #conversion
+            };
+        }
+    }
+}
@@ -67,11 +67,18 @@ pub fn cli(input: TokenStream) -> TokenStream {
             #(#command_fields),*
         }
 
-        #[allow(non_local_definitions)]
-        pub fn parse_env(__env_args: Vec<String>) -> Command {
-            // CLI setup
+        // Builds the CLI definition, so settings like colors can be changed before parsing
+        pub fn build_cli() -> dsl_cli::dsl_cli_core::Cli {
             #cli_setup
+            __cli
+        }
 
+        // Returns instead of exiting when help was shown or the command line was invalid
+        #[allow(non_local_definitions)]
+        pub fn try_parse_env_with(
+            mut __cli: dsl_cli::dsl_cli_core::Cli,
+            __env_args: Vec<String>,
+        ) -> Result<Command, dsl_cli::dsl_cli_core::ParseExit> {
             // FromParsed implementations
             use dsl_cli::dsl_cli_core::FromParsed;
             #(#args_from_parsed)*
@@ -80,6 +87,15 @@ pub fn cli(input: TokenStream) -> TokenStream {
             // Command matching and parsing
             #match_return
         }
+
+        pub fn parse_env_with(__cli: dsl_cli::dsl_cli_core::Cli, __env_args: Vec<String>) -> Command {
+            try_parse_env_with(__cli, __env_args)
+                .unwrap_or_else(|__exit| std::process::exit(__exit.exit_code()))
+        }
+
+        pub fn parse_env(__env_args: Vec<String>) -> Command {
+            parse_env_with(build_cli(), __env_args)
+        }
     };
 
     output.into()
@@ -1,13 +1,12 @@
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
-use syn::parse_quote;
 
 use crate::{
     Argument, CliDsl, CliOption, Command, generate_args_struct_name, generate_opts_struct_name,
-    get_effective_type, is_optional_type, parse_flags, to_pascal_case,
+    get_effective_type, get_option_arg_type, parse_flags, to_pascal_case,
 };
 
-pub fn generate_args_struct(args: &Vec<Argument>, pascal_prefix: &str) -> TokenStream2 {
+pub fn generate_args_struct(args: &[Argument], pascal_prefix: &str) -> TokenStream2 {
     let struct_name = format_ident!("{}", generate_args_struct_name(pascal_prefix));
 
     let fields: Vec<TokenStream2> = args
@@ -27,7 +26,7 @@ pub fn generate_args_struct(args: &Vec<Argument>, pascal_prefix: &str) -> TokenS
     }
 }
 
-pub fn generate_opts_struct(opts: &Vec<CliOption>, pascal_prefix: &str) -> TokenStream2 {
+pub fn generate_opts_struct(opts: &[CliOption], pascal_prefix: &str) -> TokenStream2 {
     let struct_name = format_ident!("{}", generate_opts_struct_name(pascal_prefix));
 
     let mut nested_structs = Vec::new();
@@ -41,20 +40,7 @@ pub fn generate_opts_struct(opts: &Vec<CliOption>, pascal_prefix: &str) -> Token
             0 => fields.push(quote! { pub #field_name: bool }),
             1 => {
                 let arg = &opt.arguments[0];
-                // Option fields should be optional if the option itself is optional,
-                // regardless of whether the argument type is non-Option.
-                //
-                // Defaults always produce a concrete value, so unwrap Option<T> when a default exists.
-                let field_type = if arg.default.is_some() {
-                    get_effective_type(arg)
-                } else if opt.required {
-                    arg.ty.clone()
-                } else if is_optional_type(&arg.ty) {
-                    arg.ty.clone()
-                } else {
-                    let ty = arg.ty.clone();
-                    parse_quote!(Option<#ty>)
-                };
+                let field_type = get_option_arg_type(arg, opt.required);
 
                 fields.push(quote! { pub #field_name: #field_type });
             }
@@ -68,16 +54,7 @@ pub fn generate_opts_struct(opts: &Vec<CliOption>, pascal_prefix: &str) -> Token
                     .iter()
                     .map(|arg| {
                         let field_name = &arg.name;
-                        let field_type = if arg.default.is_some() {
-                            get_effective_type(arg)
-                        } else if opt.required {
-                            arg.ty.clone()
-                        } else if is_optional_type(&arg.ty) {
-                            arg.ty.clone()
-                        } else {
-                            let ty = arg.ty.clone();
-                            parse_quote!(Option<#ty>)
-                        };
+                        let field_type = get_option_arg_type(arg, opt.required);
                         quote! { pub #field_name: #field_type }
                     })
                     .collect();
@@ -107,7 +84,7 @@ pub fn generate_opts_struct(opts: &Vec<CliOption>, pascal_prefix: &str) -> Token
 }
 
 // Generate the fields for the command enum
-pub fn generate_fields_for_command_enum(commands: &Vec<Command>) -> Vec<TokenStream2> {
+pub fn generate_fields_for_command_enum(commands: &[Command]) -> Vec<TokenStream2> {
     let mut fields = Vec::new();
     for cmd in commands {
         let cmd_name_pascal = to_pascal_case(&cmd.name.to_string());
@@ -151,25 +128,25 @@ pub fn generate_match_return(dsl: &CliDsl) -> TokenStream2 {
         })
         .collect();
 
-    if dsl.commands.iter().any(|cmd| cmd.name.to_string() == "cli") {
+    if dsl.commands.iter().any(|cmd| cmd.name == "cli") {
         match_arms.push(quote! {
             _ => {
-                return Command::Cli(
+                Command::Cli(
                     CliArgs::from_parsed(__parsed_args),
                     CliOpts::from_parsed(__parsed_opts)
-                );
+                )
             }
         });
     }
 
     quote! {
-        let __command_name = __env_args.first().map(|s| s.as_str()).unwrap_or("").to_string();
+        let (__parsed_args, __parsed_opts) = __cli.try_parse(__env_args)?;
 
-        let (__parsed_args, __parsed_opts) = __cli.parse(__env_args);
+        let __command_name = __cli.used_command().unwrap_or("").to_string();
 
-        match __command_name.as_str() {
+        Ok(match __command_name.as_str() {
             #(#match_arms),*
             _ => unreachable!()
-        }
+        })
     }
 }
@@ -1,5 +1,5 @@
 use crate::Argument;
-use syn::Type;
+use syn::{Type, parse_quote};
 
 /// Convert snake_case to PascalCase
 pub fn to_pascal_case(s: &str) -> String {
@@ -39,60 +39,212 @@ pub fn parse_flags(flags_str: &str) -> (Option<char>, Option<String>, String) {
 
 /// Check if a type is Option<T> or Option<Vec<T>>
 pub fn is_optional_type(ty: &Type) -> bool {
-    if let Type::Path(type_path) = ty {
-        if let Some(segment) = type_path.path.segments.first() {
-            return segment.ident == "Option";
-        }
+    if let Type::Path(type_path) = ty
+        && let Some(segment) = type_path.path.segments.first()
+    {
+        return segment.ident == "Option";
     }
     false
 }
 
 /// Check if a type is Vec<T> or Option<Vec<T>>
 pub fn is_variadic_type(ty: &Type) -> bool {
-    if let Type::Path(type_path) = ty {
-        if let Some(segment) = type_path.path.segments.first() {
-            if segment.ident == "Vec" {
-                return true;
-            }
-            if segment.ident == "Option" {
-                // Check inner type for Vec
-                if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
-                    if let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first() {
-                        return is_variadic_type(inner_ty);
-                    }
-                }
+    if let Type::Path(type_path) = ty
+        && let Some(segment) = type_path.path.segments.first()
+    {
+        if segment.ident == "Vec" {
+            return true;
+        }
+        if segment.ident == "Option" {
+            // Check inner type for Vec
+            if let syn::PathArguments::AngleBracketed(args) = &segment.arguments
+                && let Some(syn::GenericArgument::Type(inner_ty)) = args.args.first()
+            {
+                return is_variadic_type(inner_ty);
             }
         }
     }
     false
 }
 
+/// Check if a type is HashMap<K, V>, BTreeMap<K, V> or an Option of either
+pub fn is_map_type(ty: &Type) -> bool {
+    if let Some(inner) = get_inner_option_type(ty) {
+        return is_map_type(inner);
+    }
+    if let Type::Path(type_path) = ty
+        && let Some(segment) = type_path.path.segments.last()
+    {
+        return segment.ident == "HashMap" || segment.ident == "BTreeMap";
+    }
+    false
+}
+
+/// Get the key and value types of HashMap<K, V>, BTreeMap<K, V> or an Option of either
+pub fn get_map_types(ty: &Type) -> Option<(&Type, &Type)> {
+    if let Some(inner) = get_inner_option_type(ty) {
+        return get_map_types(inner);
+    }
+    if let Type::Path(type_path) = ty
+        && let Some(segment) = type_path.path.segments.last()
+        && let syn::PathArguments::AngleBracketed(args) = &segment.arguments
+        && let Some(syn::GenericArgument::Type(key)) = args.args.first()
+        && let Some(syn::GenericArgument::Type(value)) = args.args.get(1)
+    {
+        return Some((key, value));
+    }
+    None
+}
+
+/// Get the tuple or array type from (A, B), [T; N] or an Option of either
+pub fn get_fixed_arity_type(ty: &Type) -> Option<&Type> {
+    if let Some(inner) = get_inner_option_type(ty) {
+        return get_fixed_arity_type(inner);
+    }
+    match ty {
+        Type::Tuple(tuple) if !tuple.elems.is_empty() => Some(ty),
+        Type::Array(_) => Some(ty),
+        _ => None,
+    }
+}
+
+/// Get the number of values of a tuple or array type, None if the length isn't an integer literal
+pub fn get_fixed_arity(ty: &Type) -> Option<usize> {
+    match get_fixed_arity_type(ty)? {
+        Type::Tuple(tuple) => Some(tuple.elems.len()),
+        Type::Array(array) => match &array.len {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(len),
+                ..
+            }) => len.base10_parse().ok(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 /// Get the inner type from Option<T> - returns T
 pub fn get_inner_option_type(ty: &Type) -> Option<&Type> {
-    if let Type::Path(type_path) = ty {
-        if let Some(segment) = type_path.path.segments.first() {
-            if segment.ident == "Option" {
-                if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
-                    if let Some(syn::GenericArgument::Type(inner)) = args.args.first() {
-                        return Some(inner);
-                    }
+    if let Type::Path(type_path) = ty
+        && let Some(segment) = type_path.path.segments.first()
+        && segment.ident == "Option"
+        && let syn::PathArguments::AngleBracketed(args) = &segment.arguments
+        && let Some(syn::GenericArgument::Type(inner)) = args.args.first()
+    {
+        return Some(inner);
+    }
+    None
+}
+
+/// Get the type as written in the DSL, e.g. `HashMap<String, u32>` or `[u32; 3]`
+pub fn type_name(ty: &Type) -> String {
+    quote::quote!(#ty)
+        .to_string()
+        .replace(' ', "")
+        .replace(',', ", ")
+        .replace(';', "; ")
+}
+
+/// Get a default value as it should be shown in help, string literals without their quotes
+pub fn default_display(default: &syn::Expr) -> String {
+    if let syn::Expr::Lit(syn::ExprLit {
+        lit: syn::Lit::Str(lit),
+        ..
+    }) = default
+    {
+        return lit.value();
+    }
+    tokens_display(quote::quote!(#default))
+}
+
+/// Print tokens the way they are usually written, e.g. `Duration::from_secs(5 * 60)` instead of
+/// `Duration :: from_secs (5 * 60)`. Only the spaces around punctuation are dropped.
+fn tokens_display(tokens: proc_macro2::TokenStream) -> String {
+    use proc_macro2::{Delimiter, Spacing, TokenTree};
+
+    let mut out = String::new();
+    let mut prev: Option<TokenTree> = None;
+    // Whether the previous token can't be followed by a space, e.g. `::`, `.` or a unary `-`
+    let mut glued = true;
+
+    for token in tokens {
+        let space = !glued
+            && match &token {
+                // Separators, and the `!` of a macro call, e.g. `vec![..]`
+                TokenTree::Punct(punct) => {
+                    let macro_call = punct.as_char() == '!'
+                        && punct.spacing() == Spacing::Alone
+                        && matches!(prev, Some(TokenTree::Ident(_)));
+                    !(macro_call || matches!(punct.as_char(), ',' | ';' | ':' | '.' | '?'))
+                }
+                // Calls and indexing, e.g. `from_secs(..)`, `vec![..]`, `values[0]`
+                TokenTree::Group(group) => {
+                    !matches!(
+                        group.delimiter(),
+                        Delimiter::Parenthesis | Delimiter::Bracket
+                    ) || !matches!(prev, Some(TokenTree::Ident(_) | TokenTree::Group(_)))
                 }
+                _ => true,
+            };
+        if space {
+            out.push(' ');
+        }
+
+        glued = match &token {
+            TokenTree::Punct(punct) => {
+                let unary = matches!(prev, None | Some(TokenTree::Punct(_)));
+                punct.spacing() == Spacing::Joint
+                    || matches!(punct.as_char(), '.' | '!')
+                    || (punct.as_char() == ':' && out.ends_with(':'))
+                    || (unary && matches!(punct.as_char(), '-' | '&' | '*'))
+            }
+            _ => false,
+        };
+        match &token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{ ", " }"),
+                    Delimiter::None => ("", ""),
+                };
+                out.push_str(open);
+                out.push_str(&tokens_display(group.stream()));
+                out.push_str(close);
             }
+            other => out.push_str(&other.to_string()),
         }
+        prev = Some(token);
     }
-    None
+    out
 }
 
 /// Get the effective type (unwrap Option if default is provided)
 pub fn get_effective_type(arg: &Argument) -> Type {
-    if arg.default.is_some() {
-        if let Some(inner) = get_inner_option_type(&arg.ty) {
-            return inner.clone();
-        }
+    if arg.default.is_some()
+        && let Some(inner) = get_inner_option_type(&arg.ty)
+    {
+        return inner.clone();
     }
     arg.ty.clone()
 }
 
+/// Get the field type of an option argument.
+///
+/// Option fields should be optional if the option itself is optional, regardless of whether
+/// the argument type is non-Option. Defaults always produce a concrete value, so Option<T> is
+/// unwrapped when a default exists.
+pub fn get_option_arg_type(arg: &Argument, opt_required: bool) -> Type {
+    if arg.default.is_some() {
+        get_effective_type(arg)
+    } else if opt_required || is_optional_type(&arg.ty) {
+        arg.ty.clone()
+    } else {
+        let ty = arg.ty.clone();
+        parse_quote!(Option<#ty>)
+    }
+}
+
 /// Generate a struct name
 pub fn generate_args_struct_name(prefix: &str) -> String {
     format!("{}Args", prefix)
@@ -102,3 +254,23 @@ pub fn generate_args_struct_name(prefix: &str) -> String {
 pub fn generate_opts_struct_name(prefix: &str) -> String {
     format!("{}Opts", prefix)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::default_display;
+
+    #[test]
+    fn default_display_keeps_spaces_between_operands() {
+        let display = |expr: &str| default_display(&syn::parse_str(expr).unwrap());
+
+        assert_eq!(display("\"text with spaces\""), "text with spaces");
+        assert_eq!(display("-1"), "-1");
+        assert_eq!(
+            display("Duration::from_secs(5 * 60)"),
+            "Duration::from_secs(5 * 60)"
+        );
+        assert_eq!(display("vec![1, 2]"), "vec![1, 2]");
+        assert_eq!(display("a != !b"), "a != !b");
+        assert_eq!(display("0..10"), "0..10");
+    }
+}
@@ -0,0 +1,132 @@
+use std::path::PathBuf;
+
+use dsl_cli::cli;
+use dsl_cli::dsl_cli_core::{CapturedOutput, ColorChoice, ParseExit};
+
+cli! {
+    name "app",
+    version "1.0.0",
+    description "A test application",
+    completions,
+
+    cmd cp "Copy files" {
+        arg src "The files to copy": Vec<PathBuf>,
+        arg dst "The destination": PathBuf,
+        opt "-s, --size" "Width and height" {
+            arg size: (u32, u32),
+        },
+    },
+}
+
+// Runs the CLI on `args`, returning how it exited with what it wrote to stdout and stderr
+fn run(args: &[&str]) -> (Result<Command, ParseExit>, String, String) {
+    let stdout = CapturedOutput::new();
+    let stderr = CapturedOutput::new();
+    let mut cli = build_cli();
+    cli.set_executable_name("app")
+        .set_color(ColorChoice::Never)
+        .set_output(stdout.clone(), stderr.clone());
+
+    let result = try_parse_env_with(cli, args.iter().map(|arg| arg.to_string()).collect());
+    (result, stdout.contents(), stderr.contents())
+}
+
+#[test]
+fn zsh_script() {
+    let (result, stdout, stderr) = run(&["completions", "zsh"]);
+
+    assert_eq!(result.err(), Some(ParseExit::Help));
+    assert_eq!(
+        stdout,
+        "#compdef app
+
+_app() {
+    local context state state_descr line
+    typeset -A opt_args
+
+    _arguments -C \\
+        '1: :->command' \\
+        '*:: :->args'
+
+    case $state in
+        command)
+            local -a commands
+            commands=(
+                'cp:Copy files'
+                'help:Show help for a command'
+                'completions:Print a completion script for bash, zsh or fish'
+            )
+            _describe -t commands command commands
+            ;;
+        args)
+            case $words[1] in
+                'cp')
+                    _arguments \\
+                        '(-s --size)'{-s,--size}'[Width and height]:size::size:' \\
+                        '*:src or dst:_files'
+                    ;;
+                help)
+                    _arguments \\
+                        '1:command:(cp)'
+                    ;;
+                completions)
+                    _arguments \\
+                        '1:shell:(bash zsh fish)'
+                    ;;
+            esac
+            ;;
+    esac
+}
+
+if [[ $zsh_eval_context[-1] == loadautofunc ]]; then
+    _app \"$@\"
+else
+    compdef _app 'app'
+fi
+"
+    );
+    assert_eq!(stderr, "");
+}
+
+#[test]
+fn missing_shell() {
+    let (result, stdout, stderr) = run(&["completions"]);
+
+    assert_eq!(result.err(), Some(ParseExit::Error(2)));
+    assert_eq!(stdout, "");
+    assert_eq!(
+        stderr,
+        "error: Missing required arguments: <shell>
+  |
+  | app completions
+  |                 ^
+  |
+  = usage: app completions <shell>
+
+tip: Possible values are: bash, zsh, fish
+
+"
+    );
+}
+
+#[test]
+fn invalid_shell() {
+    let (result, stdout, stderr) = run(&["completions", "zhs"]);
+
+    assert_eq!(result.err(), Some(ParseExit::Error(2)));
+    assert_eq!(stdout, "");
+    assert_eq!(
+        stderr,
+        "error: Invalid value \"zhs\" for <shell>
+  |
+  | app completions zhs
+  |                 ^^^
+  |
+  = usage: app completions <shell>
+
+tip: Did you mean zsh?
+tip: Possible values are: bash, zsh, fish
+
+"
+    );
+}
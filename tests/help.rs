@@ -0,0 +1,37 @@
+use dsl_cli::cli;
+use dsl_cli::dsl_cli_core::{CapturedOutput, ColorChoice, ParseExit};
+
+cli! {
+    name "app",
+    version "1.0.0",
+    description "A test application",
+    long_about "A test application.\n\nIt only exists for the tests.",
+
+    cmd cli "Run the default action" {
+        opt "-v, --verbose" "Print more",
+    },
+}
+
+// Runs the CLI on `args`, returning how it exited with what it wrote to stdout
+fn run(args: &[&str]) -> (Result<Command, ParseExit>, String) {
+    let stdout = CapturedOutput::new();
+    let mut cli = build_cli();
+    cli.set_executable_name("app")
+        .set_help_width(80)
+        .set_color(ColorChoice::Never)
+        .set_output(stdout.clone(), CapturedOutput::new());
+
+    let result = try_parse_env_with(cli, args.iter().map(|arg| arg.to_string()).collect());
+    (result, stdout.contents())
+}
+
+#[test]
+fn top_level_help_uses_the_cli_command_description() {
+    let (result, short_help) = run(&["-h"]);
+    let (_, long_help) = run(&["help"]);
+
+    // The description of `cli` takes priority over the whole CLI's, in both forms
+    assert_eq!(result.err(), Some(ParseExit::Help));
+    assert!(short_help.contains("Run the default action"));
+    assert_eq!(short_help, long_help);
+}
@@ -0,0 +1,157 @@
+use dsl_cli::cli;
+use dsl_cli::dsl_cli_core::{CapturedOutput, ColorChoice, HelpSectionKind, ParseExit};
+
+cli! {
+    name "app",
+    version "1.0.0",
+    description "A test application",
+
+    cmd push "Push commits to a remote" {
+        arg remote "The remote to push to",
+        opt "-f, --force" "Overwrite the remote history",
+    },
+
+    cmd upload deprecated "use `push` instead" replaced_by push,
+
+    cmd status "Show the working tree status" {
+        section "Format" {
+            opt "--short" "Give the output in the short format",
+        },
+    },
+}
+
+// Runs the CLI on `args`, returning how it exited with what it wrote to stdout and stderr
+fn run(args: &[&str]) -> (Result<Command, ParseExit>, String, String) {
+    let stdout = CapturedOutput::new();
+    let stderr = CapturedOutput::new();
+    let mut cli = build_cli();
+    cli.set_executable_name("app")
+        .set_help_width(80)
+        .set_color(ColorChoice::Never)
+        .set_output(stdout.clone(), stderr.clone());
+
+    let result = try_parse_env_with(cli, args.iter().map(|arg| arg.to_string()).collect());
+    (result, stdout.contents(), stderr.contents())
+}
+
+#[test]
+fn parses_a_command() {
+    let (result, stdout, stderr) = run(&["push", "origin", "-f"]);
+
+    let Ok(Command::Push(args, opts)) = result else {
+        panic!("expected the push command");
+    };
+    assert_eq!(args.remote, "origin");
+    assert!(opts.force);
+    assert_eq!(stdout, "");
+    assert_eq!(stderr, "");
+}
+
+#[test]
+fn help_output() {
+    let (result, stdout, stderr) = run(&["help", "push"]);
+
+    assert_eq!(result.err(), Some(ParseExit::Help));
+    assert_eq!(
+        stdout,
+        "
+Usage: app push <remote> [options]
+Push commits to a remote
+
+Arguments:
+  <remote>     The remote to push to
+
+Options:
+  -f, --force  Overwrite the remote history
+
+"
+    );
+    assert_eq!(stderr, "");
+}
+
+#[test]
+fn error_output() {
+    let (result, stdout, stderr) = run(&["push", "origin", "--forse"]);
+
+    assert_eq!(result.err(), Some(ParseExit::Error(2)));
+    assert_eq!(stdout, "");
+    assert_eq!(
+        stderr,
+        "\
+error: Invalid option flag: \"--forse\"
+  |
+  | app push origin --forse
+  |                 ^^^^^^^
+  |
+  = usage: app push <remote> [options]
+
+tip: Did you mean --force?
+( For more help on options run: `app help push` )
+
+"
+    );
+}
+
+#[test]
+fn parses_twice_on_one_cli() {
+    let args = |tokens: &[&str]| tokens.iter().map(|token| token.to_string()).collect();
+    let stderr = CapturedOutput::new();
+    let mut cli = build_cli();
+    cli.set_executable_name("app")
+        .set_color(ColorChoice::Never)
+        .set_output(CapturedOutput::new(), stderr.clone());
+
+    assert!(cli.try_parse(args(&["push", "origin"])).is_ok());
+    let result = cli.try_parse(args(&["pull"]));
+
+    // Nothing is left over from `push`, e.g. its usage line
+    let (_, _, fresh_stderr) = run(&["pull"]);
+    assert_eq!(result.err(), Some(ParseExit::Error(2)));
+    assert_eq!(stderr.contents(), fresh_stderr);
+}
+
+#[test]
+fn invalid_short_flag_on_a_command_without_short_flags() {
+    let (result, stdout, stderr) = run(&["status", "-s"]);
+
+    // No tip listing the short flags, since there are none
+    assert_eq!(result.err(), Some(ParseExit::Error(2)));
+    assert_eq!(stdout, "");
+    assert_eq!(
+        stderr,
+        "\
+error: Invalid option flag: \"-s\"
+  |
+  | app status -s
+  |            ^^
+  |
+  = usage: app status [options]
+
+( For more help on options run: `app help status` )
+
+"
+    );
+}
+
+#[test]
+fn help_doc_leaves_out_empty_sections() {
+    let doc = build_cli().help_doc("status");
+
+    // Every option of `status` is under `Format`, so there is no `Options` section
+    let sections = doc
+        .sections
+        .iter()
+        .map(|section| (section.kind, section.title.as_str(), section.entries.len()))
+        .collect::<Vec<_>>();
+    assert_eq!(sections, [(HelpSectionKind::Options, "Format", 1)]);
+}
+
+#[test]
+fn help_of_a_replaced_command() {
+    let (result, stdout, stderr) = run(&["help", "upload"]);
+
+    // The help of `push`, without the deprecation warning
+    assert_eq!(result.err(), Some(ParseExit::Help));
+    assert_eq!(stdout, run(&["help", "push"]).1);
+    assert_eq!(stderr, "");
+}
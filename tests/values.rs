@@ -0,0 +1,73 @@
+use dsl_cli::cli;
+use dsl_cli::dsl_cli_core::{CapturedOutput, ColorChoice, ParseExit};
+
+cli! {
+    name "app",
+    version "1.0.0",
+    description "A test application",
+
+    cmd resize "Resize an image" {
+        opt "-s, --size" "Width and height" {
+            arg size: (u32, u32),
+        },
+        opt "-c, --color" "Red, green and blue" {
+            arg color: [u8; 3],
+        },
+    },
+}
+
+// Runs the CLI on `args`, returning how it exited with what it wrote to stderr
+fn run(args: &[&str]) -> (Result<Command, ParseExit>, String) {
+    let stderr = CapturedOutput::new();
+    let mut cli = build_cli();
+    cli.set_executable_name("app")
+        .set_color(ColorChoice::Never)
+        .set_output(CapturedOutput::new(), stderr.clone());
+
+    let result = try_parse_env_with(cli, args.iter().map(|arg| arg.to_string()).collect());
+    (result, stderr.contents())
+}
+
+#[test]
+fn parses_tuple_and_array_values() {
+    let (result, stderr) = run(&["resize", "-s", "800", "600", "-c", "1", "2", "3"]);
+
+    let Ok(Command::Resize(_, opts)) = result else {
+        panic!("expected the resize command, got: {}", stderr);
+    };
+    assert_eq!(opts.size, Some((800, 600)));
+    assert_eq!(opts.color, Some([1, 2, 3]));
+}
+
+#[test]
+fn malformed_tuple_element_is_a_parse_error() {
+    let (result, stderr) = run(&["resize", "-s", "800", "x"]);
+
+    assert_eq!(result.err(), Some(ParseExit::Error(2)));
+    assert_eq!(
+        stderr,
+        "\
+error: Invalid value \"x\" for option '-s, --size', it doesn't match the option's type
+  |
+  | app resize -s 800 x
+  |                   ^
+  |
+  = usage: app resize [options]
+
+tip: Option is defined as: -s, --size <size> <size>
+( For more help on option arguments run: `app help resize` )
+
+"
+    );
+}
+
+#[test]
+fn out_of_range_array_element_is_a_parse_error() {
+    let (result, stderr) = run(&["resize", "-c", "1", "2", "300"]);
+
+    assert_eq!(result.err(), Some(ParseExit::Error(2)));
+    assert!(stderr.starts_with(
+        "error: Invalid value \"300\" for option '-c, --color', it doesn't match the option's type\n"
+    ));
+    assert!(stderr.contains("  | app resize -c 1 2 300\n  |                   ^^^\n"));
+}